@@ -0,0 +1,95 @@
+//! Compares std's SipHash maps with `FastMap` on the kind of coordinate keys grid solvers use.
+//! Run with `cargo bench --bench hashing`.
+
+use aoc_2023::commons::hash::{FastMap, FastSet};
+use std::collections::{HashMap, HashSet};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SIDE: isize = 512;
+const ROUNDS: u32 = 5;
+
+fn coordinates() -> Vec<(isize, isize)> {
+    (-SIDE / 2..SIDE / 2)
+        .flat_map(|y| (-SIDE / 2..SIDE / 2).map(move |x| (x, y)))
+        .collect()
+}
+
+/// Best of a few runs, to keep noise from other processes out of the numbers
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, std: Duration, fast: Duration) {
+    println!(
+        "{:<16} std {:>10.3?}  fast {:>10.3?}  {:>5.2}x",
+        name,
+        std,
+        fast,
+        std.as_secs_f64() / fast.as_secs_f64()
+    );
+}
+
+fn main() {
+    let keys = coordinates();
+
+    let std_insert = time(|| {
+        let mut map = HashMap::new();
+        for &k in &keys {
+            map.insert(k, k.0 ^ k.1);
+        }
+        black_box(map);
+    });
+    let fast_insert = time(|| {
+        let mut map = FastMap::default();
+        for &k in &keys {
+            map.insert(k, k.0 ^ k.1);
+        }
+        black_box(map);
+    });
+    report("map insert", std_insert, fast_insert);
+
+    let std_map = keys
+        .iter()
+        .map(|&k| (k, k.0 ^ k.1))
+        .collect::<HashMap<_, _>>();
+    let fast_map = keys
+        .iter()
+        .map(|&k| (k, k.0 ^ k.1))
+        .collect::<FastMap<_, _>>();
+    let std_lookup = time(|| {
+        let sum = keys.iter().filter_map(|k| std_map.get(k)).sum::<isize>();
+        black_box(sum);
+    });
+    let fast_lookup = time(|| {
+        let sum = keys.iter().filter_map(|k| fast_map.get(k)).sum::<isize>();
+        black_box(sum);
+    });
+    report("map lookup", std_lookup, fast_lookup);
+
+    // Neighbour checks as in a flood fill: mostly hits, some misses off the edge
+    let std_set = keys.iter().copied().collect::<HashSet<_>>();
+    let fast_set = keys.iter().copied().collect::<FastSet<_>>();
+    let std_neighbours = time(|| {
+        let count = keys
+            .iter()
+            .filter(|(x, y)| std_set.contains(&(x + 1, *y)) && std_set.contains(&(*x, y + 1)))
+            .count();
+        black_box(count);
+    });
+    let fast_neighbours = time(|| {
+        let count = keys
+            .iter()
+            .filter(|(x, y)| fast_set.contains(&(x + 1, *y)) && fast_set.contains(&(*x, y + 1)))
+            .count();
+        black_box(count);
+    });
+    report("set neighbours", std_neighbours, fast_neighbours);
+}
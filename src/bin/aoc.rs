@@ -0,0 +1,486 @@
+use aoc_2023::commons::aocbench::{expand, BenchConfig};
+use aoc_2023::commons::cli::{example_file, Part, UsageError};
+use aoc_2023::commons::inputs::{InputStatus, Manifest, CHECKSUM_FILE};
+use aoc_2023::commons::parallel;
+use aoc_2023::commons::report::{DayReport, PartReport, Status};
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, ExitCode};
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "usage: aoc [OPTIONS] [DAY...]
+       aoc new-day DAY
+
+Runs each day with the `cmd` from .aocbench.yaml and checks its output against
+the day's `answerfile`. Days are the ones whose `daypath` exists. Build everything
+first with `cargo build --release`. The checksum of each input is kept in
+inputs.sum once its answer checks out, with a warning if the input changes later.
+
+  -d, --day N           run day N, as well as any DAYs given
+  -p, --part 1|2|both   which parts to solve and check (default: both)
+  -i, --input PATH      run each day on PATH instead of its `inputfile`, with
+                        %day% and %zeroday% standing for the day; answers are
+                        only checked for a day's own input
+  -e, --example N       run each day on inputs/examples/NN-N
+  -q, --quiet           don't print the answers, unless as JSON
+  -t, --time            print how long each part took, as the days report it
+  -j, --jobs N          run up to N days at once (default: one per core)
+  -s, --sequential      run one day at a time, and tell days not to use threads
+      --json            print a JSON object per day on each line instead, with
+                        the answers, timing, input checksum and status
+  -h, --help            show this message
+  new-day DAY           start a new day from the template, with empty input
+                        and answer files to fill in";
+
+const DAY_TEMPLATE: &str = include_str!("../../templates/day.rs");
+
+enum Action {
+    Run(Options),
+    NewDay(u32),
+}
+
+/// What to run the days on instead of their `inputfile`
+enum InputChoice {
+    /// A path, with `%day%` and `%zeroday%` standing for the day
+    File(String),
+    Example(u32),
+}
+
+struct Options {
+    days: Vec<u32>,
+    jobs: Option<usize>,
+    sequential: bool,
+    json: bool,
+    part: Part,
+    input: Option<InputChoice>,
+    quiet: bool,
+    time: bool,
+}
+
+impl Options {
+    fn set_input(&mut self, input: InputChoice) -> Result<(), String> {
+        match self.input.replace(input) {
+            Some(_) => Err(UsageError::ConflictingInputs.to_string()),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_day(s: &str) -> Result<u32, String> {
+    match s.parse() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(format!("bad day `{}`", s)),
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Action, String> {
+    let mut args = args.peekable();
+    if args.next_if(|arg| arg == "new-day").is_some() {
+        let day = args.next().ok_or("new-day needs a day")?;
+        if let Some(extra) = args.next() {
+            return Err(format!("unexpected argument `{}`", extra));
+        }
+        return Ok(Action::NewDay(parse_day(&day)?));
+    }
+
+    let mut options = Options {
+        days: Vec::new(),
+        jobs: None,
+        sequential: false,
+        json: false,
+        part: Part::Both,
+        input: None,
+        quiet: false,
+        time: false,
+    };
+
+    while let Some(arg) = args.next() {
+        // Accept `--flag=value` as well as `--flag value`, like the days do
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &'static str| {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| UsageError::MissingValue(name).to_string())
+        };
+        let bad_value = |flag, value| UsageError::BadValue { flag, value }.to_string();
+
+        match flag {
+            "-d" | "--day" => options.days.push(parse_day(&value("--day")?)?),
+            "-p" | "--part" => {
+                let part = value("--part")?;
+                options.part = Part::from_arg(&part).ok_or_else(|| bad_value("--part", part))?;
+            }
+            "-i" | "--input" => match value("--input")? {
+                path if path == "-" => {
+                    return Err("the runner can't pass standard input to the days".to_string())
+                }
+                path => options.set_input(InputChoice::File(path))?,
+            },
+            "-e" | "--example" => {
+                let n = value("--example")?;
+                let example = n.parse().ok().filter(|n| *n > 0);
+                options.set_input(InputChoice::Example(
+                    example.ok_or_else(|| bad_value("--example", n))?,
+                ))?;
+            }
+            "-q" | "--quiet" => options.quiet = true,
+            "-t" | "--time" => options.time = true,
+            "-j" | "--jobs" => {
+                let n = value("--jobs")?;
+                let n = n.parse().map_err(|_| format!("bad job count `{}`", n))?;
+                options.jobs = Some(n);
+            }
+            "-s" | "--sequential" => options.sequential = true,
+            "--json" => options.json = true,
+            "-h" | "--help" => return Err(String::new()),
+            flag if flag.starts_with('-') => {
+                return Err(UsageError::UnknownOption(flag.to_string()).to_string())
+            }
+            day => options.days.push(parse_day(day)?),
+        }
+    }
+
+    Ok(Action::Run(options))
+}
+
+/// Creates the files for a new day, refusing to touch any that already exist
+fn new_day(config: &BenchConfig, day: u32) -> Result<ExitCode, Box<dyn Error>> {
+    let files = [
+        (config.day_path(day), expand(DAY_TEMPLATE, day)),
+        (config.input_file(day), String::new()),
+        (config.answer_file(day), String::new()),
+    ];
+
+    let existing = files
+        .iter()
+        .filter(|(path, _)| Path::new(path).exists())
+        .map(|(path, _)| path.as_str())
+        .collect::<Vec<_>>();
+    if !existing.is_empty() {
+        eprintln!("not overwriting {}", existing.join(", "));
+        return Ok(ExitCode::FAILURE);
+    }
+
+    for (path, contents) in &files {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?
+            .write_all(contents.as_bytes())?;
+        println!("created {}", path);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn available_days(config: &BenchConfig) -> Vec<u32> {
+    (1..=25)
+        .filter(|day| Path::new(&config.day_path(*day)).is_file())
+        .collect()
+}
+
+struct DayResult {
+    day: u32,
+    part: Part,
+    input: String,
+    /// `None` if the input couldn't be read, in which case `output` says why
+    input_status: Option<InputStatus>,
+    output: Result<String, String>,
+    /// What the day printed to standard error, when it was asked for timings
+    timings: Option<String>,
+    /// The answers to the parts that were run
+    expected: Option<String>,
+    elapsed: Duration,
+}
+
+impl DayResult {
+    fn passed(&self) -> bool {
+        match (&self.output, &self.expected) {
+            (Ok(out), Some(expected)) => out.trim_end() == expected.trim_end(),
+            _ => false,
+        }
+    }
+
+    fn status(&self) -> Status {
+        match (&self.output, &self.expected) {
+            _ if self.input_status == Some(InputStatus::Missing) => Status::NoInput,
+            (Err(_), _) => Status::Failed,
+            (Ok(_), None) => Status::Unchecked,
+            _ if self.passed() => Status::Correct,
+            _ => Status::Wrong,
+        }
+    }
+
+    fn report(&self, manifest: &Manifest) -> DayReport {
+        let expected = self
+            .expected
+            .as_deref()
+            .map_or(Vec::new(), |e| e.lines().collect());
+        let numbers = (1..=2).filter(|part| self.part.includes(*part));
+        let parts = self.output.as_deref().map_or(Vec::new(), |out| {
+            out.lines()
+                .zip(numbers)
+                .enumerate()
+                .map(|(i, (answer, part))| PartReport {
+                    part,
+                    answer: answer.to_string(),
+                    expected: expected.get(i).map(|e| e.to_string()),
+                    nanos: None,
+                })
+                .collect()
+        });
+        let (input_status, checksum) = match &self.input_status {
+            None => (None, None),
+            Some(InputStatus::Missing) => (Some("missing"), None),
+            Some(InputStatus::Unrecorded(sum)) => (Some("unrecorded"), Some(sum.clone())),
+            Some(InputStatus::Unchanged) => (
+                Some("unchanged"),
+                manifest.get(&self.input).map(str::to_string),
+            ),
+            Some(InputStatus::Changed { current, .. }) => (Some("changed"), Some(current.clone())),
+        };
+        DayReport {
+            day: self.day,
+            status: self.status(),
+            parts,
+            error: self.output.as_ref().err().cloned(),
+            nanos: self.elapsed.as_nanos(),
+            input: self.input.clone(),
+            input_status,
+            checksum,
+        }
+    }
+}
+
+/// The answers to the parts being run, if `input` is the day's own input
+fn expected_answers(config: &BenchConfig, day: u32, input: &str, part: Part) -> Option<String> {
+    if input != config.input_file(day) {
+        return None;
+    }
+    let answers = fs::read_to_string(config.answer_file(day)).ok()?;
+    let answers = answers
+        .lines()
+        .zip(1..)
+        .filter(|(_, n)| part.includes(*n))
+        .map(|(answer, _)| answer)
+        .collect::<Vec<_>>()
+        .join("\n");
+    // A new day's answer file stays empty until the answers are known
+    Some(answers).filter(|answers| !answers.trim().is_empty())
+}
+
+fn run_day(config: &BenchConfig, manifest: &Manifest, day: u32, options: &Options) -> DayResult {
+    let input = match &options.input {
+        None => config.input_file(day),
+        Some(InputChoice::File(path)) => expand(path, day),
+        Some(InputChoice::Example(n)) => example_file(day, *n),
+    };
+    let expected = expected_answers(config, day, &input, options.part);
+    let input_status = manifest.check(&input);
+    let not_run = |output| DayResult {
+        day,
+        part: options.part,
+        input: input.clone(),
+        input_status: input_status.as_ref().ok().cloned(),
+        output: Err(output),
+        timings: None,
+        expected: expected.clone(),
+        elapsed: Duration::ZERO,
+    };
+    match (&input_status, &options.input) {
+        (Ok(InputStatus::Missing), Some(InputChoice::Example(example))) => {
+            let missing = UsageError::MissingExample {
+                example: *example,
+                day,
+                path: input.clone(),
+            };
+            return not_run(missing.to_string());
+        }
+        (Ok(InputStatus::Missing), _) => return not_run(format!("{} not found", input)),
+        (Err(e), _) => return not_run(format!("reading {}: {}", input, e)),
+        (Ok(_), _) => {}
+    }
+
+    let args = config.command(day, &input);
+    let mut cmd = Command::new(&args[0]);
+    cmd.args(&args[1..]);
+    if options.part != Part::Both {
+        cmd.args(["--part", options.part.as_arg()]);
+    }
+    if options.time {
+        cmd.arg("--time");
+    }
+    if options.sequential {
+        cmd.env(parallel::SEQUENTIAL_ENV, "1");
+    }
+
+    let start = Instant::now();
+    let output = cmd.output();
+    let elapsed = start.elapsed();
+
+    let timings = match &output {
+        Ok(out) if options.time && out.status.success() => {
+            Some(String::from_utf8_lossy(&out.stderr).into_owned())
+        }
+        _ => None,
+    };
+    let output = match output {
+        Ok(out) if out.status.success() => Ok(String::from_utf8_lossy(&out.stdout).into_owned()),
+        Ok(out) => Err(format!(
+            "{}: {}",
+            out.status,
+            String::from_utf8_lossy(&out.stderr).trim_end()
+        )),
+        Err(e) => Err(e.to_string()),
+    };
+
+    DayResult {
+        day,
+        part: options.part,
+        input,
+        input_status: input_status.ok(),
+        output,
+        timings,
+        expected,
+        elapsed,
+    }
+}
+
+fn print_result(result: &DayResult, quiet: bool) {
+    let status = match result.status() {
+        Status::Correct => "ok",
+        Status::Wrong => "WRONG",
+        Status::Failed => "FAILED",
+        status => status.as_str(),
+    };
+    println!(
+        "day {:02}  {:<9}  {:>10.3?}",
+        result.day, status, result.elapsed
+    );
+    match &result.output {
+        Ok(_) if quiet => {}
+        Ok(out) => {
+            for line in out.lines() {
+                println!("    {}", line);
+            }
+        }
+        Err(e) => println!("    {}", e),
+    }
+    for line in result.timings.iter().flat_map(|timings| timings.lines()) {
+        println!("    {}", line);
+    }
+    if let Some(InputStatus::Changed { .. }) = result.input_status {
+        println!(
+            "    warning: {} has changed since its answer was recorded",
+            result.input
+        );
+    }
+}
+
+fn main() -> Result<ExitCode, Box<dyn Error>> {
+    let action = match parse_args(std::env::args().skip(1)) {
+        Ok(action) => action,
+        // An empty message means help was asked for
+        Err(message) if message.is_empty() => {
+            println!("{}", USAGE);
+            return Ok(ExitCode::SUCCESS);
+        }
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let config = BenchConfig::load_or_default()?;
+    let mut options = match action {
+        Action::Run(options) => options,
+        Action::NewDay(day) => return new_day(&config, day),
+    };
+
+    let days = if options.days.is_empty() {
+        available_days(&config)
+    } else {
+        std::mem::take(&mut options.days)
+    };
+
+    parallel::set_sequential(options.sequential);
+    let jobs = if options.sequential {
+        1
+    } else {
+        options.jobs.unwrap_or_else(parallel::available_threads)
+    };
+
+    let mut manifest = Manifest::load(CHECKSUM_FILE)?;
+    let start = Instant::now();
+    let results = parallel::map_with_threads(&days, jobs, |day| {
+        run_day(&config, &manifest, *day, &options)
+    });
+    let total = start.elapsed();
+
+    let mut record = false;
+    for result in &results {
+        if options.json {
+            println!("{}", result.report(&manifest).to_json());
+        } else {
+            print_result(result, options.quiet);
+        }
+        if let Some(InputStatus::Unrecorded(checksum)) = &result.input_status {
+            if result.passed() {
+                manifest.record(&result.input, checksum.clone());
+                record = true;
+            }
+        }
+    }
+    if record {
+        manifest.save(CHECKSUM_FILE)?;
+    }
+
+    let missing_inputs = results
+        .iter()
+        .filter(|r| r.input_status == Some(InputStatus::Missing))
+        .map(|r| format!("{:02}", r.day))
+        .collect::<Vec<_>>();
+    let ran = results.len() - missing_inputs.len();
+    let passed = results.iter().filter(|r| r.passed()).count();
+    // Other inputs have no answers to check, so there they only need to run
+    let checked = results.iter().all(|r| r.input == config.input_file(r.day));
+    let failed = results
+        .iter()
+        .filter(|r| matches!(r.status(), Status::Wrong | Status::Failed))
+        .count();
+    let code = if (checked && passed == ran) || (!checked && failed == 0) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    };
+    if options.json {
+        return Ok(code);
+    }
+
+    if !missing_inputs.is_empty() {
+        println!("no input for days {}", missing_inputs.join(", "));
+    }
+    if checked {
+        let missing_answers = results
+            .iter()
+            .filter(|r| r.expected.is_none())
+            .map(|r| format!("{:02}", r.day))
+            .collect::<Vec<_>>();
+        if !missing_answers.is_empty() {
+            println!("no answers for days {}", missing_answers.join(", "));
+        }
+        println!("{}/{} days correct in {:.3?}", passed, ran, total);
+    } else {
+        println!("{}/{} days ran in {:.3?}", ran - failed, ran, total);
+    }
+
+    Ok(code)
+}
@@ -1,8 +1,9 @@
-use aoc_2023::commons::io::Input;
+use aoc_2023::commons::cli::Runner;
 use std::error::Error;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let input = Input::from_argv()?;
+    let mut runner = Runner::from_env(1);
+    let input = runner.input()?;
 
     let map = [
         ("1", 1, true),
@@ -25,57 +26,58 @@ fn main() -> Result<(), Box<dyn Error>> {
         ("nine", 9, false),
     ];
 
-    let (p1, p2) = input
-        .as_str()
-        .lines()
-        .map(|line| {
-            let mut p1_leftmost = None;
-            let mut p1_rightmost = None;
-            let mut p2_leftmost = None;
-            let mut p2_rightmost = None;
+    let input = input.as_str()?;
+    runner.parts(|| {
+        input
+            .lines()
+            .map(|line| {
+                let mut p1_leftmost = None;
+                let mut p1_rightmost = None;
+                let mut p2_leftmost = None;
+                let mut p2_rightmost = None;
 
-            'outer: for i in 0..line.len() {
-                let substr = &line[i..];
-                for (stringy, value, p1) in map {
-                    if substr.starts_with(stringy) {
-                        if p1 {
-                            p1_leftmost = Some(value)
-                        }
-                        if p2_leftmost.is_none() {
-                            p2_leftmost = Some(value)
-                        }
-                        if p1_leftmost.is_some() && p2_leftmost.is_some() {
-                            break 'outer;
+                'outer: for i in 0..line.len() {
+                    let substr = &line[i..];
+                    for (stringy, value, p1) in map {
+                        if substr.starts_with(stringy) {
+                            if p1 {
+                                p1_leftmost = Some(value)
+                            }
+                            if p2_leftmost.is_none() {
+                                p2_leftmost = Some(value)
+                            }
+                            if p1_leftmost.is_some() && p2_leftmost.is_some() {
+                                break 'outer;
+                            }
                         }
                     }
                 }
-            }
 
-            'outer: for i in 0..line.len() {
-                let substr = &line[..(line.len() - i)];
-                for (stringy, value, p1) in map {
-                    if substr.ends_with(stringy) {
-                        if p1 {
-                            p1_rightmost = Some(value)
-                        }
-                        if p2_rightmost.is_none() {
-                            p2_rightmost = Some(value)
-                        }
-                        if p1_rightmost.is_some() && p2_rightmost.is_some() {
-                            break 'outer;
+                'outer: for i in 0..line.len() {
+                    let substr = &line[..(line.len() - i)];
+                    for (stringy, value, p1) in map {
+                        if substr.ends_with(stringy) {
+                            if p1 {
+                                p1_rightmost = Some(value)
+                            }
+                            if p2_rightmost.is_none() {
+                                p2_rightmost = Some(value)
+                            }
+                            if p1_rightmost.is_some() && p2_rightmost.is_some() {
+                                break 'outer;
+                            }
                         }
                     }
                 }
-            }
-
-            (
-                p1_leftmost.unwrap() * 10 + p1_rightmost.unwrap(),
-                p2_leftmost.unwrap() * 10 + p2_rightmost.unwrap(),
-            )
-        })
-        .fold((0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1));
 
-    println!("{}\n{}", p1, p2);
+                (
+                    p1_leftmost.unwrap() * 10 + p1_rightmost.unwrap(),
+                    p2_leftmost.unwrap() * 10 + p2_rightmost.unwrap(),
+                )
+            })
+            .fold((0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1))
+    });
+    runner.finish();
 
     Ok(())
 }
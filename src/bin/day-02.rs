@@ -1,4 +1,4 @@
-use aoc_2023::commons::io::Input;
+use aoc_2023::commons::cli::Runner;
 use peg::str::LineCol;
 use std::error::Error;
 use std::str::FromStr;
@@ -124,12 +124,15 @@ fn part2(input: &[Game]) -> u32 {
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let input = Input::from_argv()?
-        .as_lines_parsed::<Game>()
+    let mut runner = Runner::from_env(2);
+    let input = runner
+        .input()?
+        .as_lines_parsed::<Game>()?
         .collect::<Result<Vec<_>, _>>()?;
 
-    println!("{}", part1(&input));
-    println!("{}", part2(&input));
+    runner.part(1, || part1(&input));
+    runner.part(2, || part2(&input));
+    runner.finish();
 
     Ok(())
 }
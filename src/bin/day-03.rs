@@ -0,0 +1,71 @@
+use std::error::Error;
+
+use aoc_2023::commons::cli::Runner;
+use aoc_2023::commons::grid::tokens::{NumberTokens, TokenError};
+use aoc_2023::commons::grid::{Grid, SingleVecGrid};
+
+struct Schematic {
+    numbers: NumberTokens,
+    symbols: Vec<((usize, usize), char)>,
+}
+
+fn parse(input: &str) -> Result<Schematic, TokenError> {
+    let grid = SingleVecGrid::from_rows(input.lines().map(str::chars));
+    let numbers = NumberTokens::scan(&grid)?;
+    let symbols = grid
+        .points()
+        .into_iter()
+        .filter(|(_, c)| !c.is_ascii_digit() && **c != '.')
+        .map(|(cell, c)| (cell, *c))
+        .collect();
+    Ok(Schematic { numbers, symbols })
+}
+
+fn part1(schematic: &Schematic) -> u64 {
+    schematic
+        .numbers
+        .adjacent_to_any(schematic.symbols.iter().map(|(cell, _)| *cell))
+        .iter()
+        .map(|number| number.value)
+        .sum()
+}
+
+fn part2(schematic: &Schematic) -> u64 {
+    schematic
+        .symbols
+        .iter()
+        .filter(|(_, c)| *c == '*')
+        .map(|(cell, _)| schematic.numbers.adjacent_to(*cell))
+        .filter(|adjacent| adjacent.len() == 2)
+        .map(|adjacent| adjacent[0].value * adjacent[1].value)
+        .sum()
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut runner = Runner::from_env(3);
+    let input = runner.input()?;
+    let schematic = parse(input.as_str()?)?;
+
+    runner.part(1, || part1(&schematic));
+    runner.part(2, || part2(&schematic));
+    runner.finish();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../inputs/examples/03-1");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 4361);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 467835);
+    }
+}
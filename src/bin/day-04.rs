@@ -1,5 +1,9 @@
 use std::error::Error;
 
+use aoc_2023::commons::bitset::BitSet128;
+use aoc_2023::commons::cli::Runner;
+use aoc_2023::commons::dp;
+
 #[derive(Debug)]
 pub struct Card {
     id: u32,
@@ -20,51 +24,49 @@ fn part1(input: &[Card]) -> u32 {
 }
 
 fn part2(input: &[Card]) -> u32 {
-    let mut card_counts = Vec::new();
-    card_counts.resize(input.len(), 1);
-
-    for card in input {
-        // This card's count
-        let card_count = card_counts[card.id as usize];
-        for i in 0..card.matching_count {
-            let next_card_id = card.id + i + 1;
-            card_counts[next_card_id as usize] += card_count;
-        }
-    }
+    let mut card_counts = dp::Table::new(input.len(), 1);
+    card_counts.forward(dp::sum, |i, &count| {
+        let card = &input[i];
+        let first_won = card.id as usize + 1;
+        (first_won..first_won + card.matching_count as usize).map(move |next_id| (next_id, count))
+    });
 
-    card_counts.iter().sum()
+    card_counts.values().iter().sum()
 }
 
-fn numlist_to_bitset(s: &str) -> u128 {
-    s.split(" ")
+fn numlist_to_bitset(s: &str) -> Result<BitSet128, Box<dyn Error>> {
+    let numbers = s
+        .split(' ')
         .filter(|x| !x.is_empty())
-        .map(|p| 1 << p.parse::<u32>().unwrap())
-        .fold(0_u128, |acc, x| acc | x)
+        .map(|p| p.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(BitSet128::try_from_iter(numbers)?)
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let file = std::fs::File::open("inputs/04")?;
-    let mmap = unsafe { memmap2::Mmap::map(&file)? };
-    let s = std::str::from_utf8(&mmap)?;
-    let input = s
+    let mut runner = Runner::from_env(4);
+    let input = runner.input()?;
+    let input = input
+        .as_str()?
         .lines()
         .map(|l| {
             let (card_header, card) = l.split_once(':').unwrap();
             let (_, mut card_id) = card_header.split_once(' ').unwrap();
-            while card_id.chars().next().unwrap() == ' ' {
+            while card_id.starts_with(' ') {
                 card_id = &card_id[1..];
             }
             let (winners, picks) = card.split_once(" | ").unwrap();
-            let matching_count =
-                (numlist_to_bitset(winners) & numlist_to_bitset(picks)).count_ones();
-            Card {
-                id: card_id.parse::<u32>().unwrap() - 1,
-                matching_count,
-            }
+            let matching_count = (numlist_to_bitset(winners)? & numlist_to_bitset(picks)?).len();
+            Ok(Card {
+                id: card_id.parse::<u32>()? - 1,
+                matching_count: matching_count as u32,
+            })
         })
-        .collect::<Vec<Card>>();
+        .collect::<Result<Vec<Card>, Box<dyn Error>>>()?;
 
-    println!("{}\n{}", part1(&input), part2(&input));
+    runner.part(1, || part1(&input));
+    runner.part(2, || part2(&input));
+    runner.finish();
 
     Ok(())
 }
@@ -1,141 +1,106 @@
-use aoc_2023::commons::io::Input;
-use itertools::Itertools;
-use peg::str::LineCol;
+use aoc_2023::commons::cli::Runner;
+use aoc_2023::commons::piecewise::{ConversionGraph, Mapping, Stage};
 use std::error::Error;
+use std::ops::Range;
 use std::str::FromStr;
 
 peg::parser! {
     grammar puzle_parser() for str {
         rule fileend() -> () = ("\n" / "");
 
-        rule number() -> u32
+        rule number() -> u64
             = n:$(['0'..='9']+) {? n.parse().or(Err("bad number")) }
 
-        rule number_list() -> Vec<u32>
+        rule number_list() -> Vec<u64>
             = number() ** " "
 
         rule mapping() -> Mapping
-            = dst_start:number() " " src_start:number() " " length:number() {
-                Mapping {
-                    src_range: src_start..(src_start + length),
-                    dst_start
-                }
+            = dst_start:number() " " src_start:number() " " length:number() {?
+                Mapping::new(dst_start, src_start, length).ok_or("mapping out of range")
             }
 
-        rule mapping_list() -> Vec<Mapping>
-            = mapping() ** "\n"
+        rule category() -> &'input str
+            = $(['a'..='z' | 'A'..='Z' | '0'..='9' | '_']+)
 
-        pub rule puzzle() -> Puzzle
-            = "seeds: " seeds:number_list()
-            "\n\nseed-to-soil map:\n"
-            seeds_to_soil:mapping_list()
-            "\n\nsoil-to-fertilizer map:\n"
-            soil_to_fertilizer:mapping_list()
-            "\n\nfertilizer-to-water map:\n"
-            fertilizer_to_water:mapping_list()
-            "\n\nwater-to-light map:\n"
-            water_to_light:mapping_list()
-            "\n\nlight-to-temperature map:\n"
-            light_to_temperature:mapping_list()
-            "\n\ntemperature-to-humidity map:\n"
-            temperature_to_humidity:mapping_list()
-            "\n\nhumidity-to-location map:\n"
-            humidity_to_location:mapping_list()
-            fileend() {
-                Puzzle {
-                    seeds,
-                    seeds_to_soil,
-                    soil_to_fertilizer,
-                    fertilizer_to_water,
-                    water_to_light,
-                    light_to_temperature,
-                    temperature_to_humidity,
-                    humidity_to_location,
-                }
+        rule conversion() -> Conversion<'input>
+            = from:category() "-to-" to:category() " map:\n" mappings:(mapping() ** "\n") {
+                (from, to, mappings)
             }
 
-    }
-}
-
-#[derive(Debug)]
-pub struct Mapping {
-    src_range: std::ops::Range<u32>,
-    dst_start: u32,
-}
+        pub rule puzzle() -> (Vec<u64>, Vec<Conversion<'input>>)
+            = "seeds: " seeds:number_list()
+            conversions:("\n\n" c:conversion() { c })*
+            fileend() { (seeds, conversions) }
 
-fn apply_mappings(mappings: &[Mapping], i: u32) -> u32 {
-    for mapping in mappings {
-        if mapping.src_range.contains(&i) {
-            let offset = i - mapping.src_range.start;
-            return offset + mapping.dst_start;
-        }
     }
-    i
 }
 
+type Conversion<'a> = (&'a str, &'a str, Vec<Mapping>);
+
 #[derive(Debug)]
 pub struct Puzzle {
-    seeds: Vec<u32>,
-    seeds_to_soil: Vec<Mapping>,
-    soil_to_fertilizer: Vec<Mapping>,
-    fertilizer_to_water: Vec<Mapping>,
-    water_to_light: Vec<Mapping>,
-    light_to_temperature: Vec<Mapping>,
-    temperature_to_humidity: Vec<Mapping>,
-    humidity_to_location: Vec<Mapping>,
+    seeds: Vec<u64>,
+    almanac: ConversionGraph,
 }
 
 impl FromStr for Puzzle {
-    type Err = peg::error::ParseError<LineCol>;
+    type Err = Box<dyn Error>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        puzle_parser::puzzle(s)
+        let (seeds, conversions) = puzle_parser::puzzle(s)?;
+        let mut almanac = ConversionGraph::new();
+        for (from, to, mappings) in conversions {
+            let stage =
+                Stage::new(mappings).map_err(|e| format!("{}-to-{} map: {}", from, to, e))?;
+            almanac.add(from, to, stage);
+        }
+        Ok(Puzzle { seeds, almanac })
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let input = puzle_parser::puzzle(Input::from_argv()?.as_str())?;
-
-    let part1 = input
-        .seeds
-        .iter()
-        .map(|seed| {
-            let soil = apply_mappings(&input.seeds_to_soil, *seed);
-            let fertilizer = apply_mappings(&input.soil_to_fertilizer, soil);
-            let water = apply_mappings(&input.fertilizer_to_water, fertilizer);
-            let light = apply_mappings(&input.water_to_light, water);
-            let temperature = apply_mappings(&input.light_to_temperature, light);
-            let humidity = apply_mappings(&input.temperature_to_humidity, temperature);
-            let location = apply_mappings(&input.humidity_to_location, humidity);
-            location
+fn seed_ranges(seeds: &[u64]) -> Result<Vec<Range<u64>>, Box<dyn Error>> {
+    let pairs = seeds.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err("Seed ranges need a start and a length".into());
+    }
+    pairs
+        .map(|pair| {
+            let end = pair[0]
+                .checked_add(pair[1])
+                .ok_or("Seed range out of range")?;
+            Ok(pair[0]..end)
         })
-        .min()
-        .unwrap();
-
-    println!("{}", part1);
-
-    /*
-    let mut part2 = u32::MAX;
-    for mut chunk in &input.seeds.iter().chunks(2) {
-        let a = *chunk.next().unwrap();
-        let b = *chunk.next().unwrap();
-        let chunk_ans = (a..(a + b))
-            .map(|seed| {
-                let soil = apply_mappings(&input.seeds_to_soil, seed);
-                let fertilizer = apply_mappings(&input.soil_to_fertilizer, soil);
-                let water = apply_mappings(&input.fertilizer_to_water, fertilizer);
-                let light = apply_mappings(&input.water_to_light, water);
-                let temperature = apply_mappings(&input.light_to_temperature, light);
-                let humidity = apply_mappings(&input.temperature_to_humidity, temperature);
-                let location = apply_mappings(&input.humidity_to_location, humidity);
-                location
+        .collect()
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut runner = Runner::from_env(5);
+    let input: Puzzle = runner.input()?.as_str()?.parse()?;
+
+    let seed_to_location = input.almanac.route("seed", "location")?;
+
+    runner.try_part(1, || {
+        input
+            .seeds
+            .iter()
+            .filter_map(|seed| {
+                seed_to_location
+                    .map(*seed)
+                    .first()
+                    .map(|locations| locations.start)
             })
             .min()
-            .unwrap();
-        part2 = part2.min(chunk_ans);
-    }
-    println!("{}", part2);
-    */
+            .ok_or("No seeds")
+    })?;
+
+    runner.try_part(2, || -> Result<_, Box<dyn Error>> {
+        Ok(seed_to_location
+            .map_ranges(seed_ranges(&input.seeds)?)
+            .first()
+            .map(|locations| locations.start)
+            .ok_or("No seeds")?)
+    })?;
+    runner.finish();
 
     Ok(())
 }
@@ -1,4 +1,4 @@
-use aoc_2023::commons::io::Input;
+use aoc_2023::commons::cli::Runner;
 use integer_sqrt::IntegerSquareRoot;
 use std::error::Error;
 
@@ -27,8 +27,9 @@ impl Race {
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let input = Input::from_argv()?;
-    let input = input.as_str();
+    let mut runner = Runner::from_env(6);
+    let input = runner.input()?;
+    let input = input.as_str()?;
 
     let mut lines = input.lines();
     let (_, time_line) = lines.next().unwrap().split_once(":").unwrap();
@@ -48,20 +49,23 @@ fn main() -> Result<(), Box<dyn Error>> {
         races[i].time = time.parse()?;
     }
 
-    let part1 = races
-        .iter()
-        .map(Race::record_beating_tries)
-        .product::<u64>();
+    runner.part(1, || {
+        races
+            .iter()
+            .map(Race::record_beating_tries)
+            .product::<u64>()
+    });
 
-    let part2_race = Race {
-        time: time_line.split_whitespace().collect::<String>().parse()?,
-        best_distance: distance_line
-            .split_whitespace()
-            .collect::<String>()
-            .parse()?,
-    };
-    let part2 = part2_race.record_beating_tries();
-
-    println!("{}\n{}", part1, part2);
+    runner.try_part(2, || -> Result<_, Box<dyn Error>> {
+        let race = Race {
+            time: time_line.split_whitespace().collect::<String>().parse()?,
+            best_distance: distance_line
+                .split_whitespace()
+                .collect::<String>()
+                .parse()?,
+        };
+        Ok(race.record_beating_tries())
+    })?;
+    runner.finish();
     Ok(())
 }
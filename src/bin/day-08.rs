@@ -1,5 +1,6 @@
-use aoc_2023::commons::io::Input;
+use aoc_2023::commons::cli::Runner;
 use aoc_2023::commons::math::LcmExt;
+use aoc_2023::commons::parallel;
 use std::error::Error;
 
 #[inline]
@@ -35,14 +36,14 @@ fn node_as_int(s: &str) -> u32 {
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let input = Input::from_argv()?;
-    let mut lines = input.as_str().lines();
+    let mut runner = Runner::from_env(8);
+    let input = runner.input()?;
+    let mut lines = input.as_str()?.lines();
 
     let path = lines.next().unwrap();
     lines.next();
 
-    #[allow(invalid_value)]
-    let mut nodes: [(u32, u32); 32768] = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    let mut nodes = [(0u32, 0u32); 32768];
     let mut ending_in_a = Vec::with_capacity(1_000);
     for l in lines {
         let node_name = &l[0..3];
@@ -57,15 +58,19 @@ fn main() -> Result<(), Box<dyn Error>> {
     }
 
     let target_node = node_as_int("ZZZ");
-    let part1 = path_length(&nodes, path, 0, |node| node == target_node);
+    runner.part(1, || {
+        path_length(&nodes, path, 0, |node| node == target_node)
+    });
 
-    let part2 = ending_in_a
-        .iter()
-        .map(|node_idx| path_length(&nodes, path, *node_idx, |node| node & 0b11111 == 0b11001))
+    runner.part(2, || {
+        parallel::map(&ending_in_a, |node_idx| {
+            path_length(&nodes, path, *node_idx, |node| node & 0b11111 == 0b11001)
+        })
+        .into_iter()
         .lcm()
-        .unwrap();
-
-    println!("{}\n{}", part1, part2);
+        .unwrap()
+    });
+    runner.finish();
 
     Ok(())
 }
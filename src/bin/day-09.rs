@@ -1,6 +1,6 @@
 use std::error::Error;
 
-use aoc_2023::commons::io::Input;
+use aoc_2023::commons::cli::Runner;
 
 fn next_num(seq: &mut [isize]) -> (isize, isize) {
     if seq.iter().all(|i| *i == 0) {
@@ -9,9 +9,9 @@ fn next_num(seq: &mut [isize]) -> (isize, isize) {
         let seq_len = seq.len();
         let first = seq[0];
         let mut last = seq[0];
-        for i in 1..seq_len {
-            let v = seq[i];
-            seq[i] = v - last;
+        for cell in seq.iter_mut().skip(1) {
+            let v = *cell;
+            *cell = v - last;
             last = v;
         }
         let next = next_num(&mut seq[1..seq_len]);
@@ -20,23 +20,26 @@ fn next_num(seq: &mut [isize]) -> (isize, isize) {
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let input = Input::from_argv()?;
-    let input = input.as_str();
+    let mut runner = Runner::from_env(9);
+    let input = runner.input()?;
+    let input = input.as_str()?;
 
-    let mut part1 = 0;
-    let mut part2 = 0;
-    let mut nums = Vec::with_capacity(100);
-    for line in input.lines() {
-        nums.clear();
-        for num_str in line.split_whitespace() {
-            nums.push(num_str.parse().unwrap());
+    runner.parts(|| {
+        let mut part1 = 0;
+        let mut part2 = 0;
+        let mut nums = Vec::with_capacity(100);
+        for line in input.lines() {
+            nums.clear();
+            for num_str in line.split_whitespace() {
+                nums.push(num_str.parse().unwrap());
+            }
+            let (p2, p1) = next_num(&mut nums);
+            part1 += p1;
+            part2 += p2;
         }
-        let (p2, p1) = next_num(&mut nums);
-        part1 += p1;
-        part2 += p2;
-    }
-
-    println!("{}\n{}", part1, part2);
+        (part1, part2)
+    });
+    runner.finish();
 
     Ok(())
 }
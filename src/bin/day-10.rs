@@ -1,7 +1,7 @@
 use std::error::Error;
 
+use aoc_2023::commons::cli::Runner;
 use aoc_2023::commons::grid::{BitGrid, Grid, SingleVecGrid};
-use aoc_2023::commons::io::Input;
 
 // Never eat shredded wheat (it's naaasty)
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -61,18 +61,19 @@ impl Cell {
 
     #[inline]
     pub fn can_go_north(&self) -> bool {
-        match self {
-            Self::VerticalPipe | Self::NorthEastBend | Self::NorthWestBend => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            Self::VerticalPipe | Self::NorthEastBend | Self::NorthWestBend
+        )
     }
 }
 
 const GRID_SIZE: usize = 140;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let input = Input::from_argv()?;
-    let input = input.as_str();
+    let mut runner = Runner::from_env(10);
+    let input = runner.input()?;
+    let input = input.as_str()?;
 
     let mut starting = (0, 0);
     let mut grid = SingleVecGrid::new(GRID_SIZE, GRID_SIZE);
@@ -127,26 +128,28 @@ fn main() -> Result<(), Box<dyn Error>> {
         pos = dir.step(pos);
         pipe_len += 1;
     }
-    let part1 = pipe_len.div_ceil(2);
-
-    let mut inside_count = 0;
-    for y in 0..pipe_grid.height() {
-        let mut inside = false;
-        for x in 0..pipe_grid.width() {
-            if *pipe_grid.at(&(x, y)).unwrap() {
-                let cell = grid.at(&(x, y)).unwrap();
-                if cell.can_go_north()
-                    || (*cell == Cell::StartingPosition && start_dir == Direction::North)
-                {
-                    inside = !inside;
+    runner.part(1, || pipe_len.div_ceil(2));
+
+    runner.part(2, || {
+        let mut inside_count = 0;
+        for y in 0..pipe_grid.height() {
+            let mut inside = false;
+            for x in 0..pipe_grid.width() {
+                if *pipe_grid.at(&(x, y)).unwrap() {
+                    let cell = grid.at(&(x, y)).unwrap();
+                    if cell.can_go_north()
+                        || (*cell == Cell::StartingPosition && start_dir == Direction::North)
+                    {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    inside_count += 1;
                 }
-            } else if inside {
-                inside_count += 1;
             }
         }
-    }
-
-    println!("{}\n{}", part1, inside_count);
+        inside_count
+    });
+    runner.finish();
 
     Ok(())
 }
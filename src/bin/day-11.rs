@@ -1,9 +1,9 @@
 use std::error::Error;
 
-use aoc_2023::commons::io::Input;
-use bitvec::prelude::*;
+use aoc_2023::commons::cli::Runner;
+use aoc_2023::commons::grid::{BitGrid, Grid};
+use aoc_2023::commons::parallel;
 
-const GRID_SIZE: usize = 140;
 const P2_GROWTH: usize = 1_000_000;
 
 #[inline]
@@ -13,72 +13,76 @@ fn map(point: &(usize, usize), col_mappings: &[usize], row_mappings: &[usize]) -
 
 #[inline]
 fn solve(galaxies: &[(usize, usize)], col_mappings: &[usize], row_mappings: &[usize]) -> usize {
-    let mut result = 0;
-    for i in 0..galaxies.len() {
-        let g1 = map(&galaxies[i], col_mappings, row_mappings);
-        for j in (i + 1)..galaxies.len() {
-            let g2 = map(&galaxies[j], col_mappings, row_mappings);
-            let dist = g1.0.abs_diff(g2.0) + g1.1.abs_diff(g2.1);
-            result += dist;
-        }
+    let mapped = galaxies
+        .iter()
+        .map(|g| map(g, col_mappings, row_mappings))
+        .collect::<Vec<_>>();
+    let indices = (0..mapped.len()).collect::<Vec<_>>();
+    parallel::map_reduce(
+        &indices,
+        || 0,
+        |i| {
+            let g1 = mapped[*i];
+            mapped[(i + 1)..]
+                .iter()
+                .map(|g2| g1.0.abs_diff(g2.0) + g1.1.abs_diff(g2.1))
+                .sum::<usize>()
+        },
+        |a, b| a + b,
+    )
+}
+
+fn expansion_mappings(len: usize, empty: &[usize], growth: usize) -> Vec<usize> {
+    let mut mappings = Vec::with_capacity(len);
+    let mut empty = empty.iter().peekable();
+    let mut mapped = 0;
+    for i in 0..len {
+        mappings.push(mapped);
+        mapped += if empty.next_if_eq(&&i).is_some() {
+            growth
+        } else {
+            1
+        };
     }
-    result
+    mappings
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let input = Input::from_argv()?;
-    let input = input.as_str();
-
-    let mut galaxies = Vec::with_capacity(GRID_SIZE * 10);
-    let mut p1_row_mappings = [0usize; GRID_SIZE];
-    let mut p2_row_mappings = [0usize; GRID_SIZE];
-
-    let mut column_has_galaxy = bitarr![usize, Lsb0; 0; GRID_SIZE];
+    let mut runner = Runner::from_env(11);
+    let input = runner.input()?;
+    let input = input.as_str()?;
 
-    let mut p1_mapped_row = 0;
-    let mut p2_mapped_row = 0;
+    let width = input.lines().next().map_or(0, str::len);
+    let height = input.lines().count();
+    let mut grid = BitGrid::new(width, height);
+    let mut galaxies = Vec::with_capacity(width * 10);
     for (y, line) in input.lines().enumerate() {
-        let mut line_has_galaxy = false;
         for (x, c) in line.chars().enumerate() {
             if c == '#' {
                 galaxies.push((x, y));
-                column_has_galaxy.set(x, true);
-                line_has_galaxy = true;
+                grid.set((x, y), true);
             }
         }
-        p1_row_mappings[y] = p1_mapped_row;
-        p2_row_mappings[y] = p2_mapped_row;
-        if !line_has_galaxy {
-            p1_mapped_row += 2;
-            p2_mapped_row += P2_GROWTH;
-        } else {
-            p1_mapped_row += 1;
-            p2_mapped_row += 1;
-        }
     }
 
-    let mut p1_col_mappings = [0usize; GRID_SIZE];
-    let mut p2_col_mappings = [0usize; GRID_SIZE];
-    let mut p1_mapped_col = 0;
-    let mut p2_mapped_col = 0;
-    for (x, has_galaxy) in column_has_galaxy.iter().enumerate() {
-        if x >= GRID_SIZE {
-            break;
-        }
-        p1_col_mappings[x] = p1_mapped_col;
-        p2_col_mappings[x] = p2_mapped_col;
-        if !has_galaxy {
-            p1_mapped_col += 2;
-            p2_mapped_col += P2_GROWTH;
-        } else {
-            p1_mapped_col += 1;
-            p2_mapped_col += 1;
-        }
-    }
+    let empty_rows = grid.rows_where(|cell| !cell);
+    let empty_cols = grid.columns_where(|cell| !cell);
 
-    let part1 = solve(&galaxies, &p1_col_mappings, &p1_row_mappings);
-    let part2 = solve(&galaxies, &p2_col_mappings, &p2_row_mappings);
-    println!("{}\n{}", part1, part2);
+    runner.part(1, || {
+        solve(
+            &galaxies,
+            &expansion_mappings(width, &empty_cols, 2),
+            &expansion_mappings(height, &empty_rows, 2),
+        )
+    });
+    runner.part(2, || {
+        solve(
+            &galaxies,
+            &expansion_mappings(width, &empty_cols, P2_GROWTH),
+            &expansion_mappings(height, &empty_rows, P2_GROWTH),
+        )
+    });
+    runner.finish();
 
     Ok(())
 }
@@ -0,0 +1,257 @@
+use crate::commons::hash::FastMap;
+use std::io::Error as IoError;
+use std::path::Path;
+use thiserror::Error;
+
+/// Where the runner looks for the file layout
+pub const CONFIG_FILE: &str = ".aocbench.yaml";
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("IO Error reading config")]
+    IoError(#[from] IoError),
+    #[error("Line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("Missing `{0}`")]
+    Missing(&'static str),
+    #[error("`cmd` is empty")]
+    EmptyCommand,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Value {
+    Scalar(String),
+    List(Vec<String>),
+}
+
+fn unquote(s: &str) -> &str {
+    let quoted = s.len() >= 2
+        && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')));
+    if quoted {
+        &s[1..s.len() - 1]
+    } else {
+        s
+    }
+}
+
+/// Drops a trailing comment, taking care not to cut a quoted string short
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('#', None) if i == 0 || line[..i].ends_with(' ') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parses the little YAML `.aocbench.yaml` uses: top level `key: value` pairs, where a value is
+/// a scalar, `[]`, or a list of `- item` lines following the key
+fn parse_yaml(s: &str) -> Result<FastMap<String, Value>, ConfigError> {
+    let mut values = FastMap::default();
+    let mut list: Option<(String, Vec<String>)> = None;
+    for (i, raw) in s.lines().enumerate() {
+        let line = strip_comment(raw).trim_end();
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || (i == 0 && trimmed == "---") {
+            continue;
+        }
+        let syntax = |message: &str| ConfigError::Syntax {
+            line: i + 1,
+            message: message.to_string(),
+        };
+
+        if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or((trimmed == "-").then_some(""))
+        {
+            let (_, items) = list
+                .as_mut()
+                .ok_or_else(|| syntax("list item outside a list"))?;
+            items.push(unquote(item.trim()).to_string());
+            continue;
+        }
+        if line.starts_with(' ') {
+            return Err(syntax("nested values aren't supported"));
+        }
+        if let Some((key, items)) = list.take() {
+            values.insert(key, Value::List(items));
+        }
+
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| syntax("expected `key: value`"))?;
+        let key = key.trim().to_string();
+        match value.trim() {
+            "" => list = Some((key, Vec::new())),
+            "[]" => {
+                values.insert(key, Value::List(Vec::new()));
+            }
+            value => {
+                values.insert(key, Value::Scalar(unquote(value).to_string()));
+            }
+        }
+    }
+    if let Some((key, items)) = list {
+        values.insert(key, Value::List(items));
+    }
+    Ok(values)
+}
+
+/// The file layout and run command for each day, as templates where `%zeroday%` is the day
+/// number padded to two digits, `%day%` the plain number and `%input%` the input file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    pub daypath: String,
+    pub inputfile: String,
+    pub answerfile: String,
+    pub cmd: String,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            daypath: "src/bin/day-%zeroday%.rs".to_string(),
+            inputfile: "inputs/%zeroday%".to_string(),
+            answerfile: "answers/%zeroday%".to_string(),
+            cmd: "./target/release/day-%zeroday% %input%".to_string(),
+        }
+    }
+}
+
+impl BenchConfig {
+    pub fn parse(s: &str) -> Result<Self, ConfigError> {
+        let mut values = parse_yaml(s)?;
+        let mut take = |key: &'static str| match values.remove(key) {
+            Some(Value::Scalar(v)) => Ok(v),
+            _ => Err(ConfigError::Missing(key)),
+        };
+        let cmd = take("cmd")?;
+        if cmd.trim().is_empty() {
+            return Err(ConfigError::EmptyCommand);
+        }
+        Ok(Self {
+            daypath: take("daypath")?,
+            inputfile: take("inputfile")?,
+            answerfile: take("answerfile")?,
+            cmd,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Loads `CONFIG_FILE` from the working directory, or the default layout if there isn't one
+    pub fn load_or_default() -> Result<Self, ConfigError> {
+        if Path::new(CONFIG_FILE).exists() {
+            Self::load(CONFIG_FILE)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn day_path(&self, day: u32) -> String {
+        expand(&self.daypath, day)
+    }
+
+    pub fn input_file(&self, day: u32) -> String {
+        expand(&self.inputfile, day)
+    }
+
+    pub fn answer_file(&self, day: u32) -> String {
+        expand(&self.answerfile, day)
+    }
+
+    /// The program and arguments that run `day` on `input`, split on whitespace
+    pub fn command(&self, day: u32, input: &str) -> Vec<String> {
+        self.cmd
+            .split_whitespace()
+            .map(|part| expand(part, day).replace("%input%", input))
+            .collect()
+    }
+}
+
+pub fn expand(template: &str, day: u32) -> String {
+    template
+        .replace("%zeroday%", &format!("{:02}", day))
+        .replace("%day%", &day.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "---
+# .aocbench.yaml version (always 1)
+version: 1
+author: niax # trailing comment
+cmd: \"./target/release/day-%zeroday% %input%\"
+persistence:
+ - /usr/local/cargo/registry
+environment: []
+daypath: \"src/bin/day-%zeroday%.rs\"
+inputfile: 'inputs/%zeroday%'
+answerfile: answers/%day%.txt
+common:
+- src/commons/
+- \"Cargo.toml\"
+";
+
+    #[test]
+    fn parses_subset() {
+        let values = parse_yaml(EXAMPLE).unwrap();
+        assert_eq!(values["author"], Value::Scalar("niax".to_string()));
+        assert_eq!(values["environment"], Value::List(vec![]));
+        assert_eq!(
+            values["persistence"],
+            Value::List(vec!["/usr/local/cargo/registry".to_string()])
+        );
+        assert_eq!(
+            values["common"],
+            Value::List(vec!["src/commons/".to_string(), "Cargo.toml".to_string()])
+        );
+        assert_eq!(strip_comment("a: \"x # y\" # z"), "a: \"x # y\" ");
+    }
+
+    #[test]
+    fn expands_templates() {
+        let config = BenchConfig::parse(EXAMPLE).unwrap();
+        assert_eq!(config.day_path(3), "src/bin/day-03.rs");
+        assert_eq!(config.input_file(12), "inputs/12");
+        assert_eq!(config.answer_file(7), "answers/7.txt");
+        assert_eq!(
+            config.command(5, &config.input_file(5)),
+            ["./target/release/day-05", "inputs/05"]
+        );
+    }
+
+    #[test]
+    fn default_matches_repo_config() {
+        let config = BenchConfig::load(concat!(env!("CARGO_MANIFEST_DIR"), "/.aocbench.yaml"));
+        assert_eq!(config.unwrap(), BenchConfig::default());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            BenchConfig::parse("daypath: x\ninputfile: y\ncmd: z"),
+            Err(ConfigError::Missing("answerfile"))
+        ));
+        assert!(matches!(
+            BenchConfig::parse("daypath: x\ninputfile: y\nanswerfile: z\ncmd: '  '"),
+            Err(ConfigError::EmptyCommand)
+        ));
+        assert!(matches!(
+            parse_yaml("a: 1\nnot a pair"),
+            Err(ConfigError::Syntax { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_yaml("- orphan"),
+            Err(ConfigError::Syntax { line: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,316 @@
+use std::fmt;
+use std::ops::{BitAnd, BitOr};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitSetError {
+    #[error("{value} doesn't fit in a set of width {width}")]
+    OutOfRange { value: usize, width: usize },
+}
+
+/// Fixed-width storage for a `BitSet`
+pub trait Bits: Copy + Eq {
+    const WIDTH: usize;
+
+    fn empty() -> Self;
+    /// Callers check `i < WIDTH`
+    fn get(&self, i: usize) -> bool;
+    fn set(&mut self, i: usize, value: bool);
+    fn and(self, other: Self) -> Self;
+    fn or(self, other: Self) -> Self;
+    fn count_ones(&self) -> u32;
+    fn lowest(&self) -> Option<usize>;
+}
+
+macro_rules! impl_bits {
+    ($($t:ty),*) => {
+        $(
+            impl Bits for $t {
+                const WIDTH: usize = <$t>::BITS as usize;
+
+                fn empty() -> Self {
+                    0
+                }
+
+                fn get(&self, i: usize) -> bool {
+                    self >> i & 1 == 1
+                }
+
+                fn set(&mut self, i: usize, value: bool) {
+                    if value {
+                        *self |= 1 << i;
+                    } else {
+                        *self &= !(1 << i);
+                    }
+                }
+
+                fn and(self, other: Self) -> Self {
+                    self & other
+                }
+
+                fn or(self, other: Self) -> Self {
+                    self | other
+                }
+
+                fn count_ones(&self) -> u32 {
+                    <$t>::count_ones(*self)
+                }
+
+                fn lowest(&self) -> Option<usize> {
+                    (*self != 0).then(|| self.trailing_zeros() as usize)
+                }
+            }
+        )*
+    };
+}
+
+impl_bits!(u64, u128);
+
+impl<const N: usize> Bits for [u64; N] {
+    const WIDTH: usize = 64 * N;
+
+    fn empty() -> Self {
+        [0; N]
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self[i / 64].get(i % 64)
+    }
+
+    fn set(&mut self, i: usize, value: bool) {
+        self[i / 64].set(i % 64, value)
+    }
+
+    fn and(mut self, other: Self) -> Self {
+        for (a, b) in self.iter_mut().zip(other) {
+            *a &= b;
+        }
+        self
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        for (a, b) in self.iter_mut().zip(other) {
+            *a |= b;
+        }
+        self
+    }
+
+    fn count_ones(&self) -> u32 {
+        self.iter().map(|w| w.count_ones()).sum()
+    }
+
+    fn lowest(&self) -> Option<usize> {
+        self.iter()
+            .enumerate()
+            .find_map(|(i, w)| w.lowest().map(|bit| i * 64 + bit))
+    }
+}
+
+/// A set of integers below the width of `B`
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitSet<B> {
+    bits: B,
+}
+
+pub type BitSet64 = BitSet<u64>;
+pub type BitSet128 = BitSet<u128>;
+pub type WideBitSet<const N: usize> = BitSet<[u64; N]>;
+
+impl<B: Bits> BitSet<B> {
+    pub const WIDTH: usize = B::WIDTH;
+
+    pub fn new() -> Self {
+        Self { bits: B::empty() }
+    }
+
+    /// Builds a set from `values`, failing on the first one that doesn't fit
+    pub fn try_from_iter<I>(values: I) -> Result<Self, BitSetError>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let mut set = Self::new();
+        for v in values {
+            set.insert(v)?;
+        }
+        Ok(set)
+    }
+
+    /// Adds `value`, returning whether it was newly added
+    pub fn insert(&mut self, value: usize) -> Result<bool, BitSetError> {
+        if value >= B::WIDTH {
+            return Err(BitSetError::OutOfRange {
+                value,
+                width: B::WIDTH,
+            });
+        }
+        let added = !self.bits.get(value);
+        self.bits.set(value, true);
+        Ok(added)
+    }
+
+    /// Removes `value`, returning whether it was there
+    pub fn remove(&mut self, value: usize) -> bool {
+        let present = self.contains(value);
+        if present {
+            self.bits.set(value, false);
+        }
+        present
+    }
+
+    /// Values too big for the set are never in it
+    pub fn contains(&self, value: usize) -> bool {
+        value < B::WIDTH && self.bits.get(value)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            bits: self.bits.or(other.bits),
+        }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            bits: self.bits.and(other.bits),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.lowest().is_none()
+    }
+
+    /// Members in increasing order
+    pub fn iter(&self) -> Iter<B> {
+        Iter { remaining: *self }
+    }
+}
+
+impl<B: Bits> Default for BitSet<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Bits> fmt::Debug for BitSet<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<B: Bits> BitOr for BitSet<B> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(&rhs)
+    }
+}
+
+impl<B: Bits> BitAnd for BitSet<B> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(&rhs)
+    }
+}
+
+pub struct Iter<B> {
+    remaining: BitSet<B>,
+}
+
+impl<B: Bits> Iterator for Iter<B> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let next = self.remaining.bits.lowest()?;
+        self.remaining.bits.set(next, false);
+        Some(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining.len();
+        (len, Some(len))
+    }
+}
+
+impl<B: Bits> ExactSizeIterator for Iter<B> {}
+
+impl<B: Bits> IntoIterator for BitSet<B> {
+    type Item = usize;
+    type IntoIter = Iter<B>;
+
+    fn into_iter(self) -> Iter<B> {
+        self.iter()
+    }
+}
+
+impl<B: Bits> IntoIterator for &BitSet<B> {
+    type Item = usize;
+    type IntoIter = Iter<B>;
+
+    fn into_iter(self) -> Iter<B> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_set_ops<B: Bits>() {
+        let top = BitSet::<B>::WIDTH - 1;
+        let a = BitSet::<B>::try_from_iter([0, 5, 63, top]).unwrap();
+        let b = BitSet::<B>::try_from_iter([5, 6, top]).unwrap();
+
+        assert_eq!(a.len(), if top == 63 { 3 } else { 4 });
+        assert!(a.contains(top) && !a.contains(top + 1));
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), [5, top]);
+        assert_eq!((a | b).len(), a.len() + 1);
+        assert_eq!(a.union(&b), b.union(&a));
+        assert!(a.intersection(&BitSet::new()).is_empty());
+    }
+
+    #[test]
+    fn set_ops_at_each_width() {
+        check_set_ops::<u64>();
+        check_set_ops::<u128>();
+        check_set_ops::<[u64; 4]>();
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set = BitSet128::new();
+        assert_eq!(set.insert(99), Ok(true));
+        assert_eq!(set.insert(99), Ok(false));
+        assert!(set.remove(99));
+        assert!(!set.remove(99));
+        assert!(!set.remove(500));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn rejects_values_past_width() {
+        let mut set = BitSet64::new();
+        assert_eq!(
+            set.insert(64),
+            Err(BitSetError::OutOfRange {
+                value: 64,
+                width: 64
+            })
+        );
+        assert!(set.is_empty());
+        assert!(BitSet128::try_from_iter([1, 2, 128]).is_err());
+        assert_eq!(WideBitSet::<3>::try_from_iter([191]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn iterates_in_order() {
+        let set = WideBitSet::<2>::try_from_iter([100, 3, 64, 63, 3]).unwrap();
+        let iter = set.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.collect::<Vec<_>>(), [3, 63, 64, 100]);
+        assert_eq!(format!("{:?}", set), "{3, 63, 64, 100}");
+    }
+}
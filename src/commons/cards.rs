@@ -0,0 +1,271 @@
+use thiserror::Error;
+
+/// Camel Cards ranks, lowest first
+pub const CAMEL_CARDS: &str = "23456789TJQKA";
+
+/// Camel Cards categories, weakest first: high card, one pair, two pair, three of a kind, full
+/// house, four of a kind and five of a kind
+pub const CAMEL_CATEGORIES: [&[u8]; 7] = [&[1], &[2], &[2, 2], &[3], &[3, 2], &[4], &[5]];
+
+const MAX_CARDS: usize = 12;
+const RANK_BITS: usize = 4;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HandError {
+    #[error("Unknown card {0:?}")]
+    UnknownCard(char),
+    #[error("Hands can have at most {MAX_CARDS} cards, not {0}")]
+    TooManyCards(usize),
+    #[error("Hand {0:?} isn't in any category")]
+    NoCategory(String),
+    #[error("Card {0:?} isn't ASCII")]
+    NonAsciiCard(char),
+    #[error("Card {0:?} is ranked more than once")]
+    DuplicateCard(char),
+    #[error("There can be at most {} card ranks, not {0}", (1 << RANK_BITS) - 2)]
+    TooManyRanks(usize),
+}
+
+/// Orders hands by category, then card by card from the first. Compare keys from the same
+/// `Rules` only.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandKey(u64);
+
+impl HandKey {
+    /// Index into the categories of the rules that made this key
+    pub fn category(&self) -> usize {
+        (self.0 >> (MAX_CARDS * RANK_BITS)) as usize
+    }
+}
+
+/// How to rank hands: card order, an optional wildcard and the categories hands fall into
+#[derive(Clone, Debug)]
+pub struct Rules {
+    ranks: [Option<u8>; 128],
+    categories: Vec<Vec<u8>>,
+}
+
+impl Rules {
+    /// Rules with cards ranked as in `order`, lowest first, and the Camel Cards categories
+    pub fn new(order: &str) -> Result<Self, HandError> {
+        // Rank 0 is the wildcard, and packing a key takes one more value for unused slots
+        let count = order.chars().count();
+        if count > (1 << RANK_BITS) - 2 {
+            return Err(HandError::TooManyRanks(count));
+        }
+        let mut ranks = [None; 128];
+        for (i, c) in order.chars().enumerate() {
+            let rank = ranks
+                .get_mut(c as usize)
+                .ok_or(HandError::NonAsciiCard(c))?;
+            if rank.replace(i as u8 + 1).is_some() {
+                return Err(HandError::DuplicateCard(c));
+            }
+        }
+        Ok(Self {
+            ranks,
+            categories: Vec::new(),
+        }
+        .with_categories(&CAMEL_CATEGORIES))
+    }
+
+    pub fn camel_cards() -> Self {
+        Self::new(CAMEL_CARDS).expect("Camel Cards ranks are valid")
+    }
+
+    /// Makes `card` stand in for whichever card gives the best category, while ranking below
+    /// every other card when breaking ties
+    pub fn with_wildcard(mut self, card: char) -> Result<Self, HandError> {
+        let rank = self
+            .ranks
+            .get_mut(card as usize)
+            .ok_or(HandError::NonAsciiCard(card))?;
+        *rank = Some(0);
+        Ok(self)
+    }
+
+    /// Sets the categories, weakest first. Each is the group sizes a hand needs:
+    /// `[3, 2]` is matched by any hand with three of one card and two of another. A hand takes
+    /// the strongest category it matches.
+    pub fn with_categories(mut self, categories: &[&[u8]]) -> Self {
+        self.categories = categories
+            .iter()
+            .map(|c| {
+                let mut sizes = c.to_vec();
+                sizes.sort_unstable_by(|a, b| b.cmp(a));
+                sizes
+            })
+            .collect();
+        self
+    }
+
+    /// Sizes of the groups of matching cards, largest first, and the number of wildcards
+    fn groups(ranks: &[u8]) -> (Vec<u8>, u8) {
+        let mut counts = [0u8; 1 << RANK_BITS];
+        for &r in ranks {
+            counts[r as usize] += 1;
+        }
+        let wildcards = counts[0];
+        let mut groups = counts[1..]
+            .iter()
+            .copied()
+            .filter(|&n| n > 0)
+            .collect::<Vec<_>>();
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+        (groups, wildcards)
+    }
+
+    /// How many wildcards it takes for `groups` to match `needed`. Both are largest first, and
+    /// pairing them up in that order leaves the smallest shortfall.
+    fn shortfall(needed: &[u8], groups: &[u8]) -> usize {
+        needed
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| n.saturating_sub(groups.get(i).copied().unwrap_or(0)) as usize)
+            .sum()
+    }
+
+    pub fn evaluate(&self, hand: &str) -> Result<HandKey, HandError> {
+        let ranks = hand
+            .chars()
+            .map(|c| {
+                self.ranks
+                    .get(c as usize)
+                    .copied()
+                    .flatten()
+                    .ok_or(HandError::UnknownCard(c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if ranks.len() > MAX_CARDS {
+            return Err(HandError::TooManyCards(ranks.len()));
+        }
+
+        let (groups, wildcards) = Self::groups(&ranks);
+        let category = self
+            .categories
+            .iter()
+            .rposition(|needed| Self::shortfall(needed, &groups) <= wildcards as usize)
+            .ok_or_else(|| HandError::NoCategory(hand.to_string()))?;
+
+        // Cards are packed one above their rank so a wildcard still beats an unused slot
+        let mut key = category as u64;
+        for i in 0..MAX_CARDS {
+            let packed = ranks.get(i).map_or(0, |r| r + 1);
+            key = key << RANK_BITS | packed as u64;
+        }
+        Ok(HandKey(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 5] = ["32T3K", "T55J5", "KK677", "KTJJT", "QQQJA"];
+
+    fn ranked(rules: &Rules) -> Vec<&'static str> {
+        let mut hands = EXAMPLE.to_vec();
+        hands.sort_by_key(|h| rules.evaluate(h).unwrap());
+        hands
+    }
+
+    #[test]
+    fn camel_cards() {
+        let rules = Rules::camel_cards();
+        assert_eq!(
+            ranked(&rules),
+            ["32T3K", "KTJJT", "KK677", "T55J5", "QQQJA"]
+        );
+        assert_eq!(rules.evaluate("AAAAA").unwrap().category(), 6);
+        assert_eq!(rules.evaluate("AA8AA").unwrap().category(), 5);
+        assert_eq!(rules.evaluate("23332").unwrap().category(), 4);
+        assert_eq!(rules.evaluate("23456").unwrap().category(), 0);
+        assert!(rules.evaluate("33332").unwrap() > rules.evaluate("2AAAA").unwrap());
+    }
+
+    #[test]
+    fn wildcards() {
+        let rules = Rules::camel_cards().with_wildcard('J').unwrap();
+        assert_eq!(
+            ranked(&rules),
+            ["32T3K", "KK677", "T55J5", "QQQJA", "KTJJT"]
+        );
+        assert_eq!(rules.evaluate("JJJJJ").unwrap().category(), 6);
+        assert!(rules.evaluate("JKKK2").unwrap() < rules.evaluate("QQQQ2").unwrap());
+
+        // Any card can be wild, even one that isn't otherwise ranked
+        let rules = Rules::camel_cards().with_wildcard('*').unwrap();
+        assert_eq!(rules.evaluate("2*3*4").unwrap().category(), 3);
+
+        // Wildcards go wherever makes the best category, not always into the largest group
+        let rules = Rules::camel_cards()
+            .with_categories(&[&[1], &[3], &[2, 2]])
+            .with_wildcard('J')
+            .unwrap();
+        assert_eq!(rules.evaluate("AAKJ").unwrap().category(), 2);
+        assert_eq!(rules.evaluate("AAAJ").unwrap().category(), 1);
+        assert_eq!(rules.evaluate("AKQJ").unwrap().category(), 0);
+    }
+
+    #[test]
+    fn custom_categories() {
+        // Only pairs and better count, so no-pair hands are rejected
+        let rules = Rules::new("A23456789TJQK")
+            .unwrap()
+            .with_categories(&[&[2], &[2, 2], &[3, 2]]);
+        assert_eq!(rules.evaluate("AA234").unwrap().category(), 0);
+        assert_eq!(rules.evaluate("AAA22").unwrap().category(), 2);
+        // Four of a kind doesn't make a full house, so it's only a pair here
+        assert!(rules.evaluate("KAKKK").unwrap() < rules.evaluate("AKAKA").unwrap());
+        assert_eq!(
+            rules.evaluate("A2345"),
+            Err(HandError::NoCategory("A2345".to_string()))
+        );
+    }
+
+    #[test]
+    fn bad_hands() {
+        let rules = Rules::camel_cards();
+        assert_eq!(rules.evaluate("2345X"), Err(HandError::UnknownCard('X')));
+        assert_eq!(rules.evaluate("23é45"), Err(HandError::UnknownCard('é')));
+        assert_eq!(
+            rules.evaluate(&"2".repeat(13)),
+            Err(HandError::TooManyCards(13))
+        );
+    }
+
+    #[test]
+    fn hands_of_different_lengths() {
+        // With a single category, hands are only ordered card by card
+        let rules = Rules::camel_cards()
+            .with_categories(&[&[1]])
+            .with_wildcard('J')
+            .unwrap();
+        let key = |hand| rules.evaluate(hand).unwrap();
+        assert!(key("2J") > key("2"));
+        assert!(key("2J") < key("22"));
+        assert!(key("23") > key("2"));
+        assert!(key("3") > key("2JJJ"));
+    }
+
+    #[test]
+    fn bad_rules() {
+        assert_eq!(
+            Rules::new("23456789TJQKA*!").err(),
+            Some(HandError::TooManyRanks(15))
+        );
+        assert!(Rules::new("23456789TJQKA*").is_ok());
+        assert_eq!(
+            Rules::new("2345é").err(),
+            Some(HandError::NonAsciiCard('é'))
+        );
+        assert_eq!(
+            Rules::new("23452").err(),
+            Some(HandError::DuplicateCard('2'))
+        );
+        assert_eq!(
+            Rules::camel_cards().with_wildcard('ß').err(),
+            Some(HandError::NonAsciiCard('ß'))
+        );
+    }
+}
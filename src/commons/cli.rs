@@ -0,0 +1,614 @@
+use crate::commons::aocbench::{expand, BenchConfig};
+use crate::commons::inputs::checksum;
+use crate::commons::io::{Input, InputError};
+use crate::commons::report::{DayReport, PartReport, Status};
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Where `--example N` looks for a day's examples, `%example%` being N
+pub const EXAMPLE_FILE: &str = "inputs/examples/%zeroday%-%example%";
+
+pub fn example_file(day: u32, example: u32) -> String {
+    expand(EXAMPLE_FILE, day).replace("%example%", &example.to_string())
+}
+
+const USAGE: &str = "usage: day-NN [OPTIONS] [INPUT]
+
+Solves the day's puzzle for INPUT, or standard input if none is given, printing
+the answer to each part on its own line.
+
+  -d, --day N           fail unless this is day N
+  -p, --part 1|2|both   which parts to solve (default: both)
+  -i, --input PATH|-    read the input from PATH, or standard input for `-`
+  -e, --example N       read the input from inputs/examples/NN-N
+      --json            print a JSON object with the answers, timings, input
+                        checksum and whether the answers match the day's
+                        answer file
+  -q, --quiet           don't print the answers, unless as JSON
+  -t, --time            print how long each part took to standard error
+  -h, --help            show this message";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum UsageError {
+    #[error("{0} needs a value")]
+    MissingValue(&'static str),
+    #[error("bad value `{value}` for {flag}")]
+    BadValue { flag: &'static str, value: String },
+    #[error("unknown option `{0}`")]
+    UnknownOption(String),
+    #[error("only one input can be given")]
+    ConflictingInputs,
+    #[error("this is day {actual}, not day {requested}")]
+    WrongDay { requested: u32, actual: u32 },
+    #[error("no example {example} for day {day:02} (looked in {path})")]
+    MissingExample {
+        example: u32,
+        day: u32,
+        path: String,
+    },
+    #[error("help requested")]
+    Help,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+    #[default]
+    Both,
+}
+
+impl Part {
+    /// Parses `1`, `2` or `both`
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        match arg {
+            "1" => Some(Part::One),
+            "2" => Some(Part::Two),
+            "both" => Some(Part::Both),
+            _ => None,
+        }
+    }
+
+    /// The value `--part` takes for this
+    pub fn as_arg(self) -> &'static str {
+        match self {
+            Part::One => "1",
+            Part::Two => "2",
+            Part::Both => "both",
+        }
+    }
+
+    pub fn includes(self, part: u8) -> bool {
+        matches!(
+            (self, part),
+            (Part::Both, 1 | 2) | (Part::One, 1) | (Part::Two, 2)
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum InputSource {
+    #[default]
+    Stdin,
+    File(PathBuf),
+    Example(u32),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    pub day: Option<u32>,
+    pub part: Part,
+    pub input: Option<InputSource>,
+    pub json: bool,
+    pub quiet: bool,
+    pub time: bool,
+}
+
+impl Options {
+    /// Parses the arguments after the program name. A lone positional argument is the input
+    /// path, which is how the benchmark harness passes it.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, UsageError> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            // Accept `--flag=value` as well as `--flag value`
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = |name: &'static str| {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or(UsageError::MissingValue(name))
+            };
+
+            match flag {
+                "-d" | "--day" => {
+                    let day = value("--day")?;
+                    options.day = Some(parse_number("--day", &day, 1..=25)?);
+                }
+                "-p" | "--part" => {
+                    let part = value("--part")?;
+                    options.part = Part::from_arg(&part).ok_or(UsageError::BadValue {
+                        flag: "--part",
+                        value: part,
+                    })?;
+                }
+                "-i" | "--input" => match value("--input")?.as_str() {
+                    "-" => options.set_input(InputSource::Stdin)?,
+                    path => options.set_input(InputSource::File(path.into()))?,
+                },
+                "-e" | "--example" => {
+                    let n = value("--example")?;
+                    let n = parse_number("--example", &n, 1..=u32::MAX)?;
+                    options.set_input(InputSource::Example(n))?;
+                }
+                "--json" => options.json = true,
+                "-q" | "--quiet" => options.quiet = true,
+                "-t" | "--time" => options.time = true,
+                "-h" | "--help" => return Err(UsageError::Help),
+                "-" => options.set_input(InputSource::Stdin)?,
+                flag if flag.starts_with('-') => {
+                    return Err(UsageError::UnknownOption(flag.to_string()))
+                }
+                path => options.set_input(InputSource::File(path.into()))?,
+            }
+        }
+        Ok(options)
+    }
+
+    fn set_input(&mut self, source: InputSource) -> Result<(), UsageError> {
+        match self.input.replace(source) {
+            Some(_) => Err(UsageError::ConflictingInputs),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_number(
+    flag: &'static str,
+    value: &str,
+    range: std::ops::RangeInclusive<u32>,
+) -> Result<u32, UsageError> {
+    value
+        .parse()
+        .ok()
+        .filter(|n| range.contains(n))
+        .ok_or_else(|| UsageError::BadValue {
+            flag,
+            value: value.to_string(),
+        })
+}
+
+fn usage_error(e: UsageError) -> ! {
+    eprintln!("error: {}\n\n{}", e, USAGE);
+    exit(2);
+}
+
+struct Answer {
+    part: u8,
+    value: String,
+    elapsed: Duration,
+}
+
+/// Runs the parts of a day that were asked for on the command line and reports their answers
+pub struct Runner {
+    day: u32,
+    options: Options,
+    started: Instant,
+    answers: Vec<Answer>,
+    /// Where the input came from, `-` being standard input
+    source: String,
+    checksum: Option<String>,
+    expected: Option<Vec<String>>,
+}
+
+impl Runner {
+    pub fn new(day: u32, options: Options) -> Result<Self, UsageError> {
+        match options.day {
+            Some(requested) if requested != day => Err(UsageError::WrongDay {
+                requested,
+                actual: day,
+            }),
+            _ => Ok(Self {
+                day,
+                options,
+                started: Instant::now(),
+                answers: Vec::new(),
+                source: "-".to_string(),
+                checksum: None,
+                expected: None,
+            }),
+        }
+    }
+
+    /// Parses the process arguments, printing usage and exiting if they're wrong
+    pub fn from_env(day: u32) -> Self {
+        match Options::parse(std::env::args().skip(1)).and_then(|o| Self::new(day, o)) {
+            Ok(runner) => runner,
+            Err(UsageError::Help) => {
+                println!("{}", USAGE);
+                exit(0);
+            }
+            Err(e) => usage_error(e),
+        }
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn wants(&self, part: u8) -> bool {
+        self.options.part.includes(part)
+    }
+
+    /// The file to read the input from, `None` being standard input
+    fn input_path(&self) -> Result<Option<PathBuf>, UsageError> {
+        match self.options.input.as_ref().unwrap_or(&InputSource::Stdin) {
+            InputSource::Stdin => Ok(None),
+            InputSource::File(path) => Ok(Some(path.clone())),
+            InputSource::Example(n) => {
+                let path = example_file(self.day, *n);
+                if Path::new(&path).is_file() {
+                    Ok(Some(path.into()))
+                } else {
+                    Err(UsageError::MissingExample {
+                        example: *n,
+                        day: self.day,
+                        path,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Reads the input, noting its checksum and expected answers if they're going to be reported.
+    /// Exits with a usage error if the example asked for doesn't exist.
+    pub fn input(&mut self) -> Result<Input, InputError> {
+        let path = self.input_path().unwrap_or_else(|e| usage_error(e));
+        let input = match &path {
+            Some(path) => Input::from_file(path)?,
+            None => Input::from_stdin()?,
+        };
+
+        if self.options.json {
+            self.checksum = Some(checksum(input.as_bytes()));
+            self.expected = path.as_deref().and_then(|path| {
+                let config = BenchConfig::load_or_default().ok()?;
+                self.expected_answers(&config, path)
+            });
+        }
+        if let Some(path) = path {
+            self.source = path.display().to_string();
+        }
+        Ok(input)
+    }
+
+    /// The answer file's lines, if `path` is the input `config` gives for this day
+    fn expected_answers(&self, config: &BenchConfig, path: &Path) -> Option<Vec<String>> {
+        let own_input = config.input_file(self.day);
+        let same = |a: &Path, b: &Path| match (a.canonicalize(), b.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => a == b,
+        };
+        if !same(path, Path::new(&own_input)) {
+            return None;
+        }
+        let answers = std::fs::read_to_string(config.answer_file(self.day)).ok()?;
+        Some(answers.lines().map(str::to_string).collect()).filter(|a: &Vec<_>| !a.is_empty())
+    }
+
+    pub fn part<T: Display>(&mut self, part: u8, solve: impl FnOnce() -> T) {
+        self.try_part(part, || Ok::<_, Infallible>(solve()))
+            .unwrap_or_else(|e| match e {})
+    }
+
+    /// Solves `part` if it was asked for
+    pub fn try_part<T: Display, E>(
+        &mut self,
+        part: u8,
+        solve: impl FnOnce() -> Result<T, E>,
+    ) -> Result<(), E> {
+        if self.wants(part) {
+            let start = Instant::now();
+            let value = solve()?.to_string();
+            self.answers.push(Answer {
+                part,
+                value,
+                elapsed: start.elapsed(),
+            });
+        }
+        Ok(())
+    }
+
+    /// For days that solve both parts in one go, so both share the time taken
+    pub fn parts<A: Display, B: Display>(&mut self, solve: impl FnOnce() -> (A, B)) {
+        if self.wants(1) || self.wants(2) {
+            let start = Instant::now();
+            let (a, b) = solve();
+            let elapsed = start.elapsed();
+            for (part, value) in [(1, a.to_string()), (2, b.to_string())] {
+                if self.wants(part) {
+                    self.answers.push(Answer {
+                        part,
+                        value,
+                        elapsed,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Prints the answers in the requested format
+    pub fn finish(self) {
+        if self.options.time {
+            for answer in &self.answers {
+                eprintln!("part {}: {:?}", answer.part, answer.elapsed);
+            }
+            eprintln!("total: {:?}", self.started.elapsed());
+        }
+        for line in self.output(self.started.elapsed()) {
+            println!("{}", line);
+        }
+    }
+
+    /// The lines to print. `--quiet` only hides the plain answers, as asking for JSON means
+    /// wanting it.
+    fn output(&self, elapsed: Duration) -> Vec<String> {
+        if self.options.json {
+            vec![self.report(elapsed).to_json().to_string()]
+        } else if self.options.quiet {
+            Vec::new()
+        } else {
+            self.answers.iter().map(|a| a.value.clone()).collect()
+        }
+    }
+
+    fn report(&self, elapsed: Duration) -> DayReport {
+        let parts = self
+            .answers
+            .iter()
+            .map(|answer| PartReport {
+                part: answer.part,
+                answer: answer.value.clone(),
+                expected: self
+                    .expected
+                    .as_ref()
+                    .and_then(|expected| expected.get(answer.part as usize - 1))
+                    .cloned(),
+                nanos: Some(answer.elapsed.as_nanos()),
+            })
+            .collect::<Vec<_>>();
+        DayReport {
+            day: self.day,
+            status: Status::combine(parts.iter().map(PartReport::status)),
+            parts,
+            error: None,
+            nanos: elapsed.as_nanos(),
+            input: self.source.clone(),
+            input_status: None,
+            checksum: self.checksum.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, UsageError> {
+        Options::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn parses_options() {
+        assert_eq!(parse(&[]).unwrap(), Options::default());
+        assert_eq!(
+            parse(&["--day", "5", "-p", "2", "--input=inputs/05", "--json", "-t"]).unwrap(),
+            Options {
+                day: Some(5),
+                part: Part::Two,
+                input: Some(InputSource::File("inputs/05".into())),
+                json: true,
+                quiet: false,
+                time: true,
+            }
+        );
+        // The harness passes the input as the only argument
+        assert_eq!(
+            parse(&["inputs/05"]).unwrap().input,
+            Some(InputSource::File("inputs/05".into()))
+        );
+        assert_eq!(parse(&["-i", "-"]).unwrap().input, Some(InputSource::Stdin));
+        assert_eq!(
+            parse(&["--example", "2", "-q"]).unwrap(),
+            Options {
+                input: Some(InputSource::Example(2)),
+                quiet: true,
+                ..Options::default()
+            }
+        );
+    }
+
+    #[test]
+    fn usage_errors() {
+        assert_eq!(parse(&["--part"]), Err(UsageError::MissingValue("--part")));
+        assert_eq!(
+            parse(&["--part", "3"]),
+            Err(UsageError::BadValue {
+                flag: "--part",
+                value: "3".to_string()
+            })
+        );
+        assert_eq!(
+            parse(&["--day", "26"]),
+            Err(UsageError::BadValue {
+                flag: "--day",
+                value: "26".to_string()
+            })
+        );
+        assert_eq!(
+            parse(&["--example", "0"]),
+            Err(UsageError::BadValue {
+                flag: "--example",
+                value: "0".to_string()
+            })
+        );
+        assert_eq!(
+            parse(&["--frobnicate"]),
+            Err(UsageError::UnknownOption("--frobnicate".to_string()))
+        );
+        // `-j` is the runner's `--jobs`, so `--json` has no short form
+        assert_eq!(
+            parse(&["-j"]),
+            Err(UsageError::UnknownOption("-j".to_string()))
+        );
+        assert_eq!(
+            parse(&["inputs/05", "--example", "1"]),
+            Err(UsageError::ConflictingInputs)
+        );
+        assert_eq!(parse(&["-h"]), Err(UsageError::Help));
+        assert_eq!(
+            Runner::new(4, parse(&["-d", "5"]).unwrap()).err(),
+            Some(UsageError::WrongDay {
+                requested: 5,
+                actual: 4
+            })
+        );
+    }
+
+    #[test]
+    fn finds_examples() {
+        let runner = Runner::new(3, parse(&["--example", "1"]).unwrap()).unwrap();
+        assert_eq!(
+            runner.input_path(),
+            Ok(Some(PathBuf::from("inputs/examples/03-1")))
+        );
+
+        let runner = Runner::new(3, parse(&["-e", "9"]).unwrap()).unwrap();
+        let error = runner.input_path().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no example 9 for day 03 (looked in inputs/examples/03-9)"
+        );
+    }
+
+    #[test]
+    fn runs_selected_parts() {
+        let mut runner = Runner::new(1, parse(&["--part", "2"]).unwrap()).unwrap();
+        let mut ran = Vec::new();
+        runner.part(1, || {
+            ran.push(1);
+            1
+        });
+        runner.part(2, || 281);
+        let parts = runner
+            .answers
+            .iter()
+            .map(|a| (a.part, a.value.as_str()))
+            .collect::<Vec<_>>();
+        assert!(ran.is_empty());
+        assert_eq!(parts, [(2, "281")]);
+
+        let mut runner = Runner::new(1, parse(&["--part", "1"]).unwrap()).unwrap();
+        runner.parts(|| (142, "unused"));
+        assert_eq!(runner.answers.len(), 1);
+        assert_eq!(runner.answers[0].value, "142");
+
+        let mut runner = Runner::new(1, Options::default()).unwrap();
+        assert_eq!(
+            runner.try_part(1, || Err::<u32, _>("bad input")),
+            Err("bad input")
+        );
+        assert!(runner.answers.is_empty());
+    }
+
+    #[test]
+    fn reports_answers() {
+        let mut runner = Runner::new(1, parse(&["--json"]).unwrap()).unwrap();
+        runner.answers = vec![
+            Answer {
+                part: 1,
+                value: "142".to_string(),
+                elapsed: Duration::from_nanos(10),
+            },
+            Answer {
+                part: 2,
+                value: "281".to_string(),
+                elapsed: Duration::from_nanos(20),
+            },
+        ];
+        let report = runner.report(Duration::from_nanos(50));
+        assert_eq!(report.status, Status::Unchecked);
+        assert_eq!(report.parts[1].nanos, Some(20));
+        assert_eq!(report.nanos, 50);
+
+        runner.expected = Some(vec!["142".to_string(), "280".to_string()]);
+        let report = runner.report(Duration::ZERO);
+        assert_eq!(report.parts[0].status(), Status::Correct);
+        assert_eq!(report.parts[1].expected.as_deref(), Some("280"));
+        assert_eq!(report.status, Status::Wrong);
+
+        runner.expected = Some(vec!["142".to_string()]);
+        assert_eq!(runner.report(Duration::ZERO).status, Status::Unchecked);
+        runner.answers.pop();
+        assert_eq!(runner.report(Duration::ZERO).status, Status::Correct);
+    }
+
+    #[test]
+    fn quiet_only_hides_plain_answers() {
+        let answer = || Answer {
+            part: 1,
+            value: "142".to_string(),
+            elapsed: Duration::ZERO,
+        };
+        let mut runner = Runner::new(1, parse(&["-q"]).unwrap()).unwrap();
+        runner.answers.push(answer());
+        assert!(runner.output(Duration::ZERO).is_empty());
+
+        let mut runner = Runner::new(1, parse(&["--quiet", "--json"]).unwrap()).unwrap();
+        runner.answers.push(answer());
+        let output = runner.output(Duration::ZERO);
+        assert_eq!(output.len(), 1);
+        assert!(output[0].starts_with(r#"{"day":1,"status":"unchecked","parts":[{"part":1,"#));
+
+        let mut runner = Runner::new(1, Options::default()).unwrap();
+        runner.answers.push(answer());
+        assert_eq!(runner.output(Duration::ZERO), ["142"]);
+    }
+
+    #[test]
+    fn checks_answers_for_own_input_only() {
+        let dir = std::env::temp_dir().join(format!("aoc-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = BenchConfig {
+            inputfile: dir.join("input-%zeroday%").display().to_string(),
+            answerfile: dir.join("answer-%zeroday%").display().to_string(),
+            ..BenchConfig::default()
+        };
+        std::fs::write(dir.join("input-05"), "seeds: 79 14 55 13\n").unwrap();
+        std::fs::write(dir.join("input-04"), "Card 1: 41 | 83\n").unwrap();
+        std::fs::write(dir.join("answer-05"), "35\n46\n").unwrap();
+
+        let runner = Runner::new(5, Options::default()).unwrap();
+        assert_eq!(
+            runner.expected_answers(&config, &dir.join("input-05")),
+            Some(vec!["35".to_string(), "46".to_string()])
+        );
+        // The same file by another path still counts
+        assert!(runner
+            .expected_answers(&config, &dir.join(".").join("input-05"))
+            .is_some());
+        assert_eq!(
+            runner.expected_answers(&config, &dir.join("input-04")),
+            None
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+use crate::commons::fingerprint::FingerprintExt;
+use crate::commons::hash::FastMap;
+use std::hash::Hash;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The step at which the repeating state was first seen
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as `step`
+    pub fn equivalent_step(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+}
+
+/// Spots repeated states by remembering only their fingerprints
+#[derive(Debug, Default)]
+pub struct CycleDetector {
+    seen: FastMap<u128, usize>,
+    step: usize,
+}
+
+impl CycleDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the state for the current step and moves on to the next, returning the cycle if
+    /// this state has been observed before
+    pub fn observe<S: Hash + ?Sized>(&mut self, state: &S) -> Option<Cycle> {
+        let step = self.step;
+        self.step += 1;
+        self.seen
+            .insert(state.fingerprint(), step)
+            .map(|start| Cycle {
+                start,
+                length: step - start,
+            })
+    }
+}
+
+/// Applies `step` to `state` `steps` times, skipping over whole cycles as soon as one is found.
+/// Returns the cycle if there was one.
+pub fn run_with_cycle_skip<S, F>(state: &mut S, steps: usize, mut step: F) -> Option<Cycle>
+where
+    S: Hash,
+    F: FnMut(&mut S),
+{
+    let mut detector = CycleDetector::new();
+    for i in 0..steps {
+        if let Some(cycle) = detector.observe(state) {
+            let remaining = (steps - i) % cycle.length;
+            for _ in 0..remaining {
+                step(state);
+            }
+            return Some(cycle);
+        }
+        step(state);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commons::grid::{BitGrid, Grid};
+
+    #[test]
+    fn detects_cycle() {
+        let mut detector = CycleDetector::new();
+        let states = [1, 2, 3, 4, 2];
+        let found = states.iter().find_map(|s| detector.observe(s));
+        let cycle = found.unwrap();
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 1,
+                length: 3
+            }
+        );
+        assert_eq!(cycle.equivalent_step(0), 0);
+        assert_eq!(cycle.equivalent_step(4), 1);
+        assert_eq!(cycle.equivalent_step(1_000_000_000), 1);
+    }
+
+    #[test]
+    fn skips_cycles_in_grid_simulation() {
+        // A single cell walking east around a 5 wide row: period 5 after the first step
+        let mut grid = BitGrid::new(5, 1);
+        grid.set((0, 0), true);
+        let walk = |g: &mut BitGrid| {
+            let x = g.set_cells().next().unwrap().0;
+            g.set((x, 0), false);
+            g.set(((x + 1) % 5, 0), true);
+        };
+
+        let cycle = run_with_cycle_skip(&mut grid, 1_000_000_003, walk).unwrap();
+        assert_eq!(cycle.length, 5);
+        assert_eq!(grid.set_cells().collect::<Vec<_>>(), vec![(3, 0)]);
+    }
+}
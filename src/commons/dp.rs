@@ -0,0 +1,337 @@
+use std::ops::{AddAssign, Index, IndexMut};
+
+/// Merge that adds every contribution, for counting problems. Since every contribution counts,
+/// the recorded parent is just the last one.
+pub fn sum<V: AddAssign>(acc: &mut V, v: V) -> bool {
+    *acc += v;
+    true
+}
+
+/// Merge that keeps the largest contribution, recording where it came from
+pub fn max<V: PartialOrd>(acc: &mut V, v: V) -> bool {
+    let better = v > *acc;
+    if better {
+        *acc = v;
+    }
+    better
+}
+
+/// Merge that keeps the smallest contribution, recording where it came from
+pub fn min<V: PartialOrd>(acc: &mut V, v: V) -> bool {
+    let better = v < *acc;
+    if better {
+        *acc = v;
+    }
+    better
+}
+
+/// Follows parent links back from `to` and returns the path in forward order
+fn backtrack<K: Copy>(to: K, parent: impl Fn(K) -> Option<K>) -> Vec<K> {
+    let mut path = vec![to];
+    let mut at = to;
+    while let Some(p) = parent(at) {
+        path.push(p);
+        at = p;
+    }
+    path.reverse();
+    path
+}
+
+/// A DP table over a sequence, filled by pushing each entry's value forward to later entries
+#[derive(Clone, Debug)]
+pub struct Table<V> {
+    values: Vec<V>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<V> Table<V> {
+    pub fn new(len: usize, init: V) -> Self
+    where
+        V: Clone,
+    {
+        Self {
+            values: vec![init; len],
+            parents: vec![None; len],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Visits every entry in order, and for each merges the `(target, value)` pairs `step`
+    /// returns into their targets. Targets must come after the entry they're pushed from, so
+    /// each entry is final by the time it's visited. When `merge` reports a change, the entry is
+    /// recorded as the target's parent.
+    pub fn forward<M, F, I>(&mut self, mut merge: M, mut step: F)
+    where
+        M: FnMut(&mut V, V) -> bool,
+        F: FnMut(usize, &V) -> I,
+        I: IntoIterator<Item = (usize, V)>,
+    {
+        for i in 0..self.values.len() {
+            let (done, rest) = self.values.split_at_mut(i + 1);
+            for (target, v) in step(i, &done[i]) {
+                assert!(
+                    target > i && target < self.parents.len(),
+                    "Can't push from {} to {}",
+                    i,
+                    target
+                );
+                if merge(&mut rest[target - i - 1], v) {
+                    self.parents[target] = Some(i);
+                }
+            }
+        }
+    }
+
+    pub fn parent(&self, i: usize) -> Option<usize> {
+        self.parents[i]
+    }
+
+    /// The chain of entries that led to `i`, starting from one nothing was pushed into
+    pub fn path_to(&self, i: usize) -> Vec<usize> {
+        backtrack(i, |i| self.parents[i])
+    }
+}
+
+impl<V> Index<usize> for Table<V> {
+    type Output = V;
+
+    fn index(&self, i: usize) -> &V {
+        &self.values[i]
+    }
+}
+
+impl<V> IndexMut<usize> for Table<V> {
+    fn index_mut(&mut self, i: usize) -> &mut V {
+        &mut self.values[i]
+    }
+}
+
+/// A DP table over a grid, indexed by `(x, y)` and filled in row-major order
+#[derive(Clone, Debug)]
+pub struct GridTable<V> {
+    width: usize,
+    height: usize,
+    values: Vec<V>,
+    parents: Vec<Option<(usize, usize)>>,
+}
+
+impl<V> GridTable<V> {
+    pub fn new(width: usize, height: usize, init: V) -> Self
+    where
+        V: Clone,
+    {
+        Self {
+            width,
+            height,
+            values: vec![init; width * height],
+            parents: vec![None; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, (x, y): (usize, usize)) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside a {}x{} table",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        y * self.width + x
+    }
+
+    /// Like `Table::forward`, visiting cells in row-major order. Targets must be later in that
+    /// order: to the right on the same row, or on a lower row.
+    pub fn forward<M, F, I>(&mut self, mut merge: M, mut step: F)
+    where
+        M: FnMut(&mut V, V) -> bool,
+        F: FnMut((usize, usize), &V) -> I,
+        I: IntoIterator<Item = ((usize, usize), V)>,
+    {
+        let (width, height) = (self.width, self.height);
+        for i in 0..self.values.len() {
+            let from = (i % width, i / width);
+            let (done, rest) = self.values.split_at_mut(i + 1);
+            for ((x, y), v) in step(from, &done[i]) {
+                let target = y * width + x;
+                assert!(
+                    x < width && y < height && target > i,
+                    "Can't push from {:?} to {:?}",
+                    from,
+                    (x, y)
+                );
+                if merge(&mut rest[target - i - 1], v) {
+                    self.parents[target] = Some(from);
+                }
+            }
+        }
+    }
+
+    pub fn parent(&self, at: (usize, usize)) -> Option<(usize, usize)> {
+        self.parents[self.index_of(at)]
+    }
+
+    /// The chain of cells that led to `at`, starting from one nothing was pushed into
+    pub fn path_to(&self, at: (usize, usize)) -> Vec<(usize, usize)> {
+        backtrack(at, |at| self.parent(at))
+    }
+}
+
+impl<V> Index<(usize, usize)> for GridTable<V> {
+    type Output = V;
+
+    fn index(&self, at: (usize, usize)) -> &V {
+        &self.values[self.index_of(at)]
+    }
+}
+
+impl<V> IndexMut<(usize, usize)> for GridTable<V> {
+    fn index_mut(&mut self, at: (usize, usize)) -> &mut V {
+        let i = self.index_of(at);
+        &mut self.values[i]
+    }
+}
+
+/// Keeps just the previous and current rows of a DP where each row only depends on the one
+/// before it, so memory is linear in the width rather than the whole table
+#[derive(Clone, Debug)]
+pub struct RollingRows<V> {
+    previous: Vec<V>,
+    current: Vec<V>,
+    rows: usize,
+}
+
+impl<V> RollingRows<V> {
+    pub fn new(first: Vec<V>) -> Self {
+        Self {
+            current: Vec::with_capacity(first.len()),
+            previous: first,
+            rows: 1,
+        }
+    }
+
+    /// Works out the next row one cell at a time. `f` gets the column, the last full row and
+    /// the cells of the new row to its left.
+    pub fn next_row<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &[V], &[V]) -> V,
+    {
+        self.current.clear();
+        for x in 0..self.previous.len() {
+            let v = f(x, &self.previous, &self.current);
+            self.current.push(v);
+        }
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.rows += 1;
+    }
+
+    /// The most recently completed row
+    pub fn row(&self) -> &[V] {
+        &self.previous
+    }
+
+    /// How many rows have been completed, including the first
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_counts_scratchcards() {
+        // Day 4 example: how many cards each one wins copies of
+        let matches = [4, 2, 2, 1, 0, 0];
+        let mut copies = Table::new(matches.len(), 1u32);
+        copies.forward(sum, |i, &count| {
+            (i + 1..=i + matches[i]).map(move |j| (j, count))
+        });
+        assert_eq!(copies.values(), &[1, 2, 4, 8, 14, 1]);
+        assert_eq!(copies.values().iter().sum::<u32>(), 30);
+    }
+
+    #[test]
+    fn table_backtracks_longest_increasing_run() {
+        let xs = [3, 1, 4, 1, 5, 9, 2, 6];
+        let mut lengths = Table::new(xs.len(), 1);
+        lengths.forward(max, |i, &len| {
+            (i + 1..xs.len())
+                .filter(move |&j| xs[j] > xs[i])
+                .map(move |j| (j, len + 1))
+        });
+        let (end, &best) = (0..xs.len())
+            .map(|i| (i, &lengths[i]))
+            .max_by_key(|(_, len)| **len)
+            .unwrap();
+        assert_eq!(best, 4);
+        let path = lengths.path_to(end);
+        assert_eq!(
+            path.iter().map(|&i| xs[i]).collect::<Vec<_>>(),
+            [3, 4, 5, 6]
+        );
+        assert_eq!(lengths.parent(0), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn table_rejects_backward_pushes() {
+        let mut t = Table::new(3, 0);
+        t.forward(sum, |i, _| Some((i.saturating_sub(1), 1)));
+    }
+
+    #[test]
+    fn grid_table_cheapest_path() {
+        let costs = [[1, 3, 1], [1, 5, 1], [4, 2, 1]];
+        let mut best = GridTable::new(3, 3, u32::MAX);
+        best[(0, 0)] = costs[0][0];
+        best.forward(min, |(x, y), &cost| {
+            [(x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| x < 3 && y < 3)
+                .map(move |(x, y)| ((x, y), cost + costs[y][x]))
+        });
+        assert_eq!(best[(2, 2)], 7);
+        assert_eq!(
+            best.path_to((2, 2)),
+            [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn rolling_rows_edit_distance() {
+        let (a, b) = (b"kitten", b"sitting");
+        let mut rows = RollingRows::new((0..=b.len()).collect());
+        for (i, ca) in a.iter().enumerate() {
+            rows.next_row(|x, above, left| {
+                if x == 0 {
+                    return i + 1;
+                }
+                let substitute = above[x - 1] + (*ca != b[x - 1]) as usize;
+                substitute.min(above[x] + 1).min(left[x - 1] + 1)
+            });
+        }
+        assert_eq!(rows.rows(), a.len() + 1);
+        assert_eq!(rows.row()[b.len()], 3);
+    }
+}
@@ -0,0 +1,100 @@
+use std::hash::{Hash, Hasher};
+
+const K1: u64 = 0x9e37_79b9_7f4a_7c15;
+const K2: u64 = 0xc2b2_ae3d_27d4_eb4f;
+
+#[inline]
+fn mix(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// A fast, deterministic two-lane hasher for telling states apart. Not DoS resistant, and the
+/// 64-bit `finish` is just the low lane - use `finish128` for fingerprints.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fingerprinter {
+    a: u64,
+    b: u64,
+    len: u64,
+}
+
+impl Fingerprinter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn finish128(&self) -> u128 {
+        let a = mix(self.a ^ self.len);
+        let b = mix(self.b.wrapping_add(self.len).wrapping_add(a));
+        (b as u128) << 64 | a as u128
+    }
+}
+
+impl Hasher for Fingerprinter {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.write_u64(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rem = chunks.remainder();
+        if !rem.is_empty() {
+            let mut buf = [0u8; 8];
+            buf[..rem.len()].copy_from_slice(rem);
+            self.write_u64(u64::from_le_bytes(buf));
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, word: u64) {
+        self.a = (self.a.rotate_left(5) ^ word).wrapping_mul(K1);
+        self.b = (self.b.rotate_left(29) ^ word).wrapping_mul(K2);
+        self.len = self.len.wrapping_add(1);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i as u64)
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i as u64)
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    fn finish(&self) -> u64 {
+        self.finish128() as u64
+    }
+}
+
+pub trait FingerprintExt: Hash {
+    /// A 128-bit digest of the value, cheap enough to store for every step of a simulation
+    fn fingerprint(&self) -> u128 {
+        let mut hasher = Fingerprinter::new();
+        self.hash(&mut hasher);
+        hasher.finish128()
+    }
+}
+
+impl<T: Hash + ?Sized> FingerprintExt for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_are_stable_and_distinct() {
+        assert_eq!((1, 2, "abc").fingerprint(), (1, 2, "abc").fingerprint());
+        assert_ne!((1, 2).fingerprint(), (2, 1).fingerprint());
+        assert_ne!(vec![0u8; 8].fingerprint(), vec![0u8; 9].fingerprint());
+        assert_ne!([0u64].fingerprint(), [0u64, 0].fingerprint());
+    }
+}
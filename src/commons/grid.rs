@@ -1,11 +1,17 @@
+use crate::commons::fingerprint::FingerprintExt;
+use crate::commons::hash::FastMap;
 use bitvec::prelude::*;
 use lazy_static::lazy_static;
-use std::cmp;
-use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+pub mod bitboard;
+pub mod regions;
+pub mod scale;
+pub mod tokens;
 
 lazy_static! {
-    static ref LETTERS: HashMap<u32, char> = {
-        let mut h = HashMap::new();
+    static ref LETTERS: FastMap<u32, char> = {
+        let mut h = FastMap::default();
         h.insert(529680320, 'A');
         h.insert(1067881856, 'B');
         h.insert(512103552, 'C');
@@ -38,74 +44,473 @@ lazy_static! {
     static ref ADJACENT: Vec<(isize, isize)> = vec![(0, -1), (0, 1), (1, 0), (-1, 0)];
 }
 
+pub trait GridCoordinate: Copy + Eq + Hash {
+    /// Moves the coordinate by `delta`, or `None` if that would leave the coordinate space
+    fn offset(&self, delta: (isize, isize)) -> Option<Self>;
+    /// The per-axis minimum of the two coordinates
+    fn min_each(&self, other: &Self) -> Self;
+    /// The per-axis maximum of the two coordinates
+    fn max_each(&self, other: &Self) -> Self;
+}
+
+impl GridCoordinate for (usize, usize) {
+    fn offset(&self, delta: (isize, isize)) -> Option<Self> {
+        Some((
+            self.0.checked_add_signed(delta.0)?,
+            self.1.checked_add_signed(delta.1)?,
+        ))
+    }
+
+    fn min_each(&self, other: &Self) -> Self {
+        (self.0.min(other.0), self.1.min(other.1))
+    }
+
+    fn max_each(&self, other: &Self) -> Self {
+        (self.0.max(other.0), self.1.max(other.1))
+    }
+}
+
+impl GridCoordinate for (isize, isize) {
+    fn offset(&self, delta: (isize, isize)) -> Option<Self> {
+        Some((self.0.checked_add(delta.0)?, self.1.checked_add(delta.1)?))
+    }
+
+    fn min_each(&self, other: &Self) -> Self {
+        (self.0.min(other.0), self.1.min(other.1))
+    }
+
+    fn max_each(&self, other: &Self) -> Self {
+        (self.0.max(other.0), self.1.max(other.1))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The `(x, y)` step for this direction, with north being towards `y = 0`
+    pub fn delta(&self) -> (isize, isize) {
+        match self {
+            Self::North => (0, -1),
+            Self::NorthEast => (1, -1),
+            Self::East => (1, 0),
+            Self::SouthEast => (1, 1),
+            Self::South => (0, 1),
+            Self::SouthWest => (-1, 1),
+            Self::West => (-1, 0),
+            Self::NorthWest => (-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for (isize, isize) {
+    fn from(dir: Direction) -> Self {
+        dir.delta()
+    }
+}
+
 pub trait Grid {
     type Value;
-    type Coordinate;
+    type Coordinate: GridCoordinate;
 
     fn height(&self) -> usize;
     fn width(&self) -> usize;
     fn at(&self, coord: &Self::Coordinate) -> Option<&Self::Value>;
     fn set(&mut self, coord: Self::Coordinate, value: Self::Value);
 
+    /// Whether `coord` is within the grid, even if there is no value there
+    fn in_bounds(&self, coord: &Self::Coordinate) -> bool {
+        self.at(coord).is_some()
+    }
+
     fn points(&self) -> Vec<(Self::Coordinate, &Self::Value)>;
     fn from_rows(source: impl IntoIterator<Item = impl IntoIterator<Item = Self::Value>>) -> Self;
+
+    /// Walks from `from` in steps of `step` until leaving the grid. The origin is not included
+    /// unless asked for with [`RaycastIterator::include_origin`].
+    fn raycast(
+        &self,
+        from: Self::Coordinate,
+        step: impl Into<(isize, isize)>,
+    ) -> RaycastIterator<'_, Self>
+    where
+        Self: Sized,
+    {
+        RaycastIterator::new(self, from, step.into())
+    }
+
+    fn north_from(&self, from: Self::Coordinate) -> RaycastIterator<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.raycast(from, Direction::North)
+    }
+
+    fn south_from(&self, from: Self::Coordinate) -> RaycastIterator<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.raycast(from, Direction::South)
+    }
+
+    fn west_from(&self, from: Self::Coordinate) -> RaycastIterator<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.raycast(from, Direction::West)
+    }
+
+    fn east_from(&self, from: Self::Coordinate) -> RaycastIterator<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.raycast(from, Direction::East)
+    }
+
+    fn row(&self, y: usize) -> Line<'_, Self>
+    where
+        Self: Sized + Grid<Coordinate = (usize, usize)>,
+    {
+        let len = if y < self.height() { self.width() } else { 0 };
+        Line::new(self, (0, y), Axis::Row, len)
+    }
+
+    fn column(&self, x: usize) -> Line<'_, Self>
+    where
+        Self: Sized + Grid<Coordinate = (usize, usize)>,
+    {
+        let len = if x < self.width() { self.height() } else { 0 };
+        Line::new(self, (x, 0), Axis::Column, len)
+    }
+
+    fn rows(&self) -> Lines<'_, Self>
+    where
+        Self: Sized + Grid<Coordinate = (usize, usize)>,
+    {
+        Lines::new(self, Axis::Row, self.height())
+    }
+
+    fn columns(&self) -> Lines<'_, Self>
+    where
+        Self: Sized + Grid<Coordinate = (usize, usize)>,
+    {
+        Lines::new(self, Axis::Column, self.width())
+    }
+
+    /// Indices of the rows where every cell satisfies `predicate`
+    fn rows_where<F>(&self, predicate: F) -> Vec<usize>
+    where
+        Self: Sized + Grid<Coordinate = (usize, usize)>,
+        F: Fn(&Self::Value) -> bool,
+    {
+        (0..self.height())
+            .filter(|y| self.row(*y).all(&predicate))
+            .collect()
+    }
+
+    /// Indices of the columns where every cell satisfies `predicate`
+    fn columns_where<F>(&self, predicate: F) -> Vec<usize>
+    where
+        Self: Sized + Grid<Coordinate = (usize, usize)>,
+        F: Fn(&Self::Value) -> bool,
+    {
+        (0..self.width())
+            .filter(|x| self.column(*x).all(&predicate))
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Axis {
+    Row,
+    Column,
+}
+
+/// A single row or column of a grid
+pub struct Line<'a, G> {
+    grid: &'a G,
+    pos: (usize, usize),
+    axis: Axis,
+    remaining: usize,
 }
 
-trait FullGrid: Grid {
-    fn row_for_point(p: &Self::Coordinate) -> usize;
-    fn column_for_point(p: &Self::Coordinate) -> usize;
+impl<'a, G> Line<'a, G> {
+    fn new(grid: &'a G, pos: (usize, usize), axis: Axis, len: usize) -> Self {
+        Self {
+            grid,
+            pos,
+            axis,
+            remaining: len,
+        }
+    }
 }
 
+impl<'a, G, T: 'a> Iterator for Line<'a, G>
+where
+    G: Grid<Value = T, Coordinate = (usize, usize)>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let value = self.grid.at(&self.pos);
+        self.remaining -= 1;
+        match self.axis {
+            Axis::Row => self.pos.0 += 1,
+            Axis::Column => self.pos.1 += 1,
+        }
+        value
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, G, T: 'a> ExactSizeIterator for Line<'a, G> where
+    G: Grid<Value = T, Coordinate = (usize, usize)>
+{
+}
+
+/// All of the rows or columns of a grid, in order
+pub struct Lines<'a, G> {
+    grid: &'a G,
+    axis: Axis,
+    next: usize,
+    end: usize,
+}
+
+impl<'a, G> Lines<'a, G> {
+    fn new(grid: &'a G, axis: Axis, end: usize) -> Self {
+        Self {
+            grid,
+            axis,
+            next: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, G> Iterator for Lines<'a, G>
+where
+    G: Grid<Coordinate = (usize, usize)>,
+{
+    type Item = Line<'a, G>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let i = self.next;
+        self.next += 1;
+        Some(match self.axis {
+            Axis::Row => self.grid.row(i),
+            Axis::Column => self.grid.column(i),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, G> ExactSizeIterator for Lines<'a, G> where G: Grid<Coordinate = (usize, usize)> {}
+
+/// A bit grid that grows in any direction to fit whatever is set in it. The backing storage covers
+/// `origin()` to `max()`, which may be larger than the set cells as growth is geometric.
+#[derive(Clone, Debug)]
 pub struct ResizingBitGrid {
     values: BitVec,
+    origin: (isize, isize),
     width: usize,
     height: usize,
 }
 
 impl ResizingBitGrid {
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_origin((0, 0), width, height)
+    }
+
+    pub fn with_origin(origin: (isize, isize), width: usize, height: usize) -> Self {
         Self {
             values: bitvec![0; width * height],
+            origin,
             width,
             height,
         }
     }
 
-    fn index_in(&self, x: usize, y: usize, width: usize, height: usize) -> Option<usize> {
-        if x >= width || y >= height {
+    pub fn origin(&self) -> (isize, isize) {
+        self.origin
+    }
+
+    /// The bottom-right corner of the backing storage, or `None` if it is empty
+    pub fn max(&self) -> Option<(isize, isize)> {
+        if self.width == 0 || self.height == 0 {
             None
         } else {
-            Some(width * y + x)
+            Some((
+                self.origin.0 + self.width as isize - 1,
+                self.origin.1 + self.height as isize - 1,
+            ))
         }
     }
 
-    fn index(&self, x: usize, y: usize) -> Option<usize> {
-        self.index_in(x, y, self.width, self.height)
+    fn index(&self, x: isize, y: isize) -> Option<usize> {
+        let x = usize::try_from(x.checked_sub(self.origin.0)?).ok()?;
+        let y = usize::try_from(y.checked_sub(self.origin.1)?).ok()?;
+        if x >= self.width || y >= self.height {
+            None
+        } else {
+            Some(self.width * y + x)
+        }
     }
 
-    fn resize(&mut self, new_width: usize, new_height: usize) {
-        let mut new_values = bitvec![0; new_width * new_height];
-        for i in self.values.iter_ones() {
-            let x = i % self.width;
-            let y = i / self.width;
-            let new_i = self
-                .index_in(x, y, new_width, new_height)
-                .expect("We really should have an index here");
-            new_values.set(new_i, true);
+    fn coord(&self, i: usize) -> (isize, isize) {
+        (
+            self.origin.0 + (i % self.width) as isize,
+            self.origin.1 + (i / self.width) as isize,
+        )
+    }
+
+    /// Moves the backing storage to cover `width`×`height` cells from `origin`, which must
+    /// contain every set cell.
+    fn relocate(&mut self, origin: (isize, isize), width: usize, height: usize) {
+        let mut new_values = bitvec![0; width * height];
+        if self.width > 0 {
+            let dx = (self.origin.0 - origin.0) as usize;
+            let dy = (self.origin.1 - origin.1) as usize;
+            for (y, row) in self.values.chunks_exact(self.width).enumerate() {
+                let start = (y + dy) * width + dx;
+                new_values[start..start + self.width].copy_from_bitslice(row);
+            }
         }
         self.values = new_values;
-        self.height = new_height;
-        self.width = new_width;
+        self.origin = origin;
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Grows the backing storage to include `coord`, at least doubling any dimension that grows
+    /// so repeated expansion is amortised O(1)
+    fn grow_to(&mut self, coord: (isize, isize)) {
+        let (x, y) = coord;
+        let max = self.max().unwrap_or(self.origin);
+        let (mut min_x, mut min_y) = self.origin;
+        let (mut max_x, mut max_y) = max;
+        if self.width == 0 || self.height == 0 {
+            (min_x, min_y, max_x, max_y) = (x, y, x, y);
+        }
+
+        let grow_x = self.width.max(1) as isize;
+        let grow_y = self.height.max(1) as isize;
+        if x < min_x {
+            min_x = x.min(min_x - grow_x);
+        } else if x > max_x {
+            max_x = x.max(max_x + grow_x);
+        }
+        if y < min_y {
+            min_y = y.min(min_y - grow_y);
+        } else if y > max_y {
+            max_y = y.max(max_y + grow_y);
+        }
+
+        if self.values.not_any() {
+            *self = Self::with_origin(
+                (min_x, min_y),
+                (max_x - min_x + 1) as usize,
+                (max_y - min_y + 1) as usize,
+            );
+        } else {
+            self.relocate(
+                (min_x, min_y),
+                (max_x - min_x + 1) as usize,
+                (max_y - min_y + 1) as usize,
+            );
+        }
+    }
+
+    /// Shrinks the backing storage to the bounding box of the set cells
+    pub fn shrink_to_fit(&mut self) {
+        let first_row = (0..self.height).find(|y| self.row_bits(*y).any());
+        let last_row = (0..self.height).rev().find(|y| self.row_bits(*y).any());
+        let (first_row, last_row) = match (first_row, last_row) {
+            (Some(first), Some(last)) => (first, last),
+            _ => {
+                *self = Self::with_origin(self.origin, 0, 0);
+                return;
+            }
+        };
+
+        let mut columns = bitvec![0; self.width];
+        for y in first_row..=last_row {
+            *columns.as_mut_bitslice() |= self.row_bits(y);
+        }
+        let first_col = columns.first_one().unwrap();
+        let last_col = columns.last_one().unwrap();
+
+        let width = last_col - first_col + 1;
+        let height = last_row - first_row + 1;
+        let mut new_values = bitvec![0; width * height];
+        for (new_y, y) in (first_row..=last_row).enumerate() {
+            let row = &self.row_bits(y)[first_col..=last_col];
+            new_values[new_y * width..(new_y + 1) * width].copy_from_bitslice(row);
+        }
+        self.values = new_values;
+        self.origin = (
+            self.origin.0 + first_col as isize,
+            self.origin.1 + first_row as isize,
+        );
+        self.width = width;
+        self.height = height;
+    }
+
+    fn row_bits(&self, y: usize) -> &BitSlice {
+        &self.values[y * self.width..(y + 1) * self.width]
     }
 
     pub fn set_cell_count(&self) -> usize {
         self.values.count_ones()
     }
+
+    pub fn set_cells(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.values.iter_ones().map(|i| self.coord(i))
+    }
 }
 
 impl Grid for ResizingBitGrid {
     type Value = bool;
-    type Coordinate = (usize, usize);
+    type Coordinate = (isize, isize);
 
     fn height(&self) -> usize {
         self.height
@@ -125,19 +530,11 @@ impl Grid for ResizingBitGrid {
 
         let mut idx = self.index(x, y);
         if idx.is_none() {
-            let delta_x = if x >= self.width {
-                x + 1 - self.width
-            } else {
-                0
-            };
-            let delta_y = if y >= self.height {
-                y + 1 - self.height
-            } else {
-                0
-            };
-            let new_width = self.width.max(self.width + delta_x * 2);
-            let new_height = self.height.max(self.height + delta_y * 2);
-            self.resize(new_width, new_height);
+            if !value {
+                // Everything outside the grid is already unset
+                return;
+            }
+            self.grow_to(coord);
             idx = self.index(x, y);
         }
         let i = idx.expect("Should now have index in range!");
@@ -145,15 +542,50 @@ impl Grid for ResizingBitGrid {
     }
 
     fn points(&self) -> Vec<(Self::Coordinate, &Self::Value)> {
-        todo!()
+        self.values
+            .iter()
+            .by_refs()
+            .enumerate()
+            .map(|(i, value)| (self.coord(i), value))
+            .collect()
+    }
+
+    fn from_rows(source: impl IntoIterator<Item = impl IntoIterator<Item = Self::Value>>) -> Self {
+        // Size the grid up front, as `set` skips unset cells outside the grid
+        let rows = source
+            .into_iter()
+            .map(|row| row.into_iter().collect::<BitVec>())
+            .collect::<Vec<_>>();
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut grid = ResizingBitGrid::with_origin((0, 0), width, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            grid.values[y * width..y * width + row.len()].copy_from_bitslice(row);
+        }
+        grid
     }
+}
 
-    fn from_rows(_: impl IntoIterator<Item = impl IntoIterator<Item = Self::Value>>) -> Self {
-        todo!()
+/// Grids are equal when the same cells are set, however much storage each has grown
+impl PartialEq for ResizingBitGrid {
+    fn eq(&self, other: &Self) -> bool {
+        self.set_cell_count() == other.set_cell_count()
+            && self.set_cells().all(|c| other.at(&c) == Some(&true))
     }
 }
 
-#[derive(Clone)]
+impl Eq for ResizingBitGrid {}
+
+impl Hash for ResizingBitGrid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Set cells come out in row-major order regardless of the storage layout
+        for cell in self.set_cells() {
+            cell.hash(state);
+        }
+        self.set_cell_count().hash(state);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BitGrid {
     values: BitVec,
     width: usize,
@@ -181,6 +613,53 @@ impl BitGrid {
         self.values.count_ones()
     }
 
+    pub fn set_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.values
+            .iter_ones()
+            .map(|i| (i % self.width, i / self.width))
+    }
+
+    pub fn row_bits(&self, y: usize) -> Option<&BitSlice> {
+        if y >= self.height {
+            None
+        } else {
+            Some(&self.values[y * self.width..(y + 1) * self.width])
+        }
+    }
+
+    // These shadow the `Grid` versions so calls on a `BitGrid` walk the bits directly rather
+    // than looking up every cell. Generic code still gets the `Grid` versions.
+
+    pub fn row(&self, y: usize) -> impl ExactSizeIterator<Item = &bool> + '_ {
+        self.row_bits(y)
+            .unwrap_or(BitSlice::empty())
+            .iter()
+            .by_refs()
+    }
+
+    pub fn column(&self, x: usize) -> impl ExactSizeIterator<Item = &bool> + '_ {
+        let bits = if x < self.width {
+            &self.values[x..]
+        } else {
+            BitSlice::empty()
+        };
+        bits.iter().by_refs().step_by(self.width.max(1))
+    }
+
+    pub fn rows(
+        &self,
+    ) -> impl ExactSizeIterator<Item = impl ExactSizeIterator<Item = &bool> + '_> + '_ {
+        self.values
+            .chunks_exact(self.width.max(1))
+            .map(|row| row.iter().by_refs())
+    }
+
+    pub fn columns(
+        &self,
+    ) -> impl ExactSizeIterator<Item = impl ExactSizeIterator<Item = &bool> + '_> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+
     pub fn print(&self, true_val: char, false_val: char) {
         for y in 0..self.height() {
             let row = (0..self.width())
@@ -217,6 +696,17 @@ impl BitGrid {
     }
 }
 
+impl Hash for BitGrid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
+        // Hash whole words rather than going bit by bit like BitSlice does
+        for chunk in self.values.chunks(64) {
+            state.write_u64(chunk.load_le::<u64>());
+        }
+    }
+}
+
 impl Grid for BitGrid {
     type Value = bool;
     type Coordinate = (usize, usize);
@@ -244,46 +734,128 @@ impl Grid for BitGrid {
     }
 
     fn points(&self) -> Vec<(Self::Coordinate, &Self::Value)> {
-        panic!("Not implemented");
+        self.values
+            .iter()
+            .by_refs()
+            .enumerate()
+            .map(|(i, value)| ((i % self.width, i / self.width), value))
+            .collect()
     }
 
-    fn from_rows(_: impl IntoIterator<Item = impl IntoIterator<Item = bool>>) -> Self {
-        panic!("Not implemented");
+    fn from_rows(source: impl IntoIterator<Item = impl IntoIterator<Item = bool>>) -> Self {
+        let mut values = BitVec::new();
+        let mut width = None;
+        let mut height = 0;
+        for row in source {
+            let start = values.len();
+            values.extend(row);
+            let row_width = values.len() - start;
+            if let Some(w) = width {
+                assert_eq!(w, row_width);
+            } else {
+                width = Some(row_width);
+            }
+            height += 1;
+        }
+        Self {
+            values,
+            width: width.unwrap_or(0),
+            height,
+        }
+    }
+
+    fn rows_where<F>(&self, predicate: F) -> Vec<usize>
+    where
+        F: Fn(&bool) -> bool,
+    {
+        let (set_ok, unset_ok) = (predicate(&true), predicate(&false));
+        (0..self.height)
+            .filter(|y| {
+                let row = self.row_bits(*y).unwrap();
+                (set_ok || row.not_any()) && (unset_ok || row.all())
+            })
+            .collect()
+    }
+
+    fn columns_where<F>(&self, predicate: F) -> Vec<usize>
+    where
+        F: Fn(&bool) -> bool,
+    {
+        let (set_ok, unset_ok) = (predicate(&true), predicate(&false));
+        if self.width == 0 {
+            return Vec::new();
+        }
+
+        // Fold the rows together so each column is checked in one pass over the words
+        let mut any_set = bitvec![0; self.width];
+        let mut all_set = bitvec![1; self.width];
+        for row in self.values.chunks_exact(self.width) {
+            *any_set.as_mut_bitslice() |= row;
+            *all_set.as_mut_bitslice() &= row;
+        }
+        (0..self.width)
+            .filter(|x| (set_ok || !any_set[*x]) && (unset_ok || all_set[*x]))
+            .collect()
     }
 }
 
-pub struct RaycastIterator<'a, G> {
+type RaycastStop<'a, G> = Box<dyn Fn(&<G as Grid>::Coordinate, &<G as Grid>::Value) -> bool + 'a>;
+
+/// Yields `(coordinate, value)` pairs along a ray until it leaves the grid, skipping coordinates
+/// in bounds that have no value, like a [`SparseGrid`]'s empty cells.
+pub struct RaycastIterator<'a, G: Grid> {
     grid: &'a G,
     step: (isize, isize),
-    pos: (isize, isize),
+    origin: G::Coordinate,
+    pos: Option<G::Coordinate>,
+    stop: Option<RaycastStop<'a, G>>,
 }
 
-impl<'a, G, T: 'a> Iterator for RaycastIterator<'a, G>
-where
-    G: Grid<Value = T, Coordinate = (usize, usize)>,
-{
-    type Item = &'a T;
+impl<'a, G: Grid> RaycastIterator<'a, G> {
+    fn new(grid: &'a G, origin: G::Coordinate, step: (isize, isize)) -> Self {
+        Self {
+            grid,
+            step,
+            origin,
+            pos: origin.offset(step),
+            stop: None,
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let was = self.pos;
-        self.pos = (self.pos.0 + self.step.0, self.pos.1 + self.step.1);
+    /// Starts the ray at the origin rather than one step away from it
+    pub fn include_origin(mut self) -> Self {
+        self.pos = Some(self.origin);
+        self
+    }
 
-        let x = if was.0 >= 0 {
-            was.0 as usize
-        } else {
-            return None;
-        };
-        let y = if was.1 >= 0 {
-            was.1 as usize
-        } else {
-            return None;
-        };
+    /// Ends the ray after the first cell matching `predicate`, which is still yielded
+    pub fn until<P>(mut self, predicate: P) -> Self
+    where
+        P: Fn(&G::Coordinate, &G::Value) -> bool + 'a,
+    {
+        self.stop = Some(Box::new(predicate));
+        self
+    }
+}
+
+impl<'a, G: Grid> Iterator for RaycastIterator<'a, G> {
+    type Item = (G::Coordinate, &'a G::Value);
 
-        self.grid.at(&(x, y))
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let coord = self.pos.take().filter(|c| self.grid.in_bounds(c))?;
+            self.pos = coord.offset(self.step);
+            if let Some(value) = self.grid.at(&coord) {
+                if self.stop.as_ref().is_some_and(|stop| stop(&coord, value)) {
+                    self.pos = None;
+                }
+                return Some((coord, value));
+            }
+        }
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct SingleVecGrid<T> {
     values: Vec<T>,
     width: usize,
@@ -327,34 +899,6 @@ where
             })
     }
 
-    pub fn raycast(&self, from: (usize, usize), step: (isize, isize)) -> RaycastIterator<Self> {
-        RaycastIterator {
-            grid: self,
-            step,
-            pos: (from.0 as isize, from.1 as isize),
-        }
-    }
-
-    pub fn north_from(&self, coord: (usize, usize)) -> impl Iterator<Item = Option<&T>> {
-        let (x, y) = coord;
-        (0..y).rev().map(move |y| self.at(&(x, y)))
-    }
-
-    pub fn south_from(&self, coord: (usize, usize)) -> impl Iterator<Item = Option<&T>> {
-        let (x, y) = coord;
-        (y + 1..self.height()).rev().map(move |y| self.at(&(x, y)))
-    }
-
-    pub fn west_from(&self, coord: (usize, usize)) -> impl Iterator<Item = Option<&T>> {
-        let (x, y) = coord;
-        (0..x).rev().map(move |x| self.at(&(x, y)))
-    }
-
-    pub fn east_from(&self, coord: (usize, usize)) -> impl Iterator<Item = Option<&T>> {
-        let (x, y) = coord;
-        (x + 1..self.width()).rev().map(move |x| self.at(&(x, y)))
-    }
-
     fn index(&self, x: usize, y: usize) -> Option<usize> {
         if x >= self.width || y >= self.height {
             None
@@ -405,8 +949,26 @@ where
             .collect()
     }
 
-    fn from_rows(_: impl IntoIterator<Item = impl IntoIterator<Item = T>>) -> Self {
-        panic!("Not implemented");
+    fn from_rows(source: impl IntoIterator<Item = impl IntoIterator<Item = T>>) -> Self {
+        let mut values = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for row in source {
+            let start = values.len();
+            values.extend(row);
+            let row_width = values.len() - start;
+            if let Some(w) = width {
+                assert_eq!(w, row_width);
+            } else {
+                width = Some(row_width);
+            }
+            height += 1;
+        }
+        Self {
+            values,
+            width: width.unwrap_or(0),
+            height,
+        }
     }
 }
 
@@ -423,7 +985,7 @@ where
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct VecGrid<T> {
     rows: Vec<Vec<T>>,
     width: Option<usize>,
@@ -452,7 +1014,7 @@ impl<T> VecGrid<T> {
             // There are all empty edges when we don't have any content
             vec![Vec::new(), Vec::new(), Vec::new(), Vec::new()]
         } else {
-            let top = self.rows.get(0).unwrap().iter().collect();
+            let top = self.rows.first().unwrap().iter().collect();
             let bottom = self.rows.get(self.height() - 1).unwrap().iter().collect();
             let mut right = Vec::with_capacity(self.height());
             let mut left = Vec::with_capacity(self.height());
@@ -528,32 +1090,77 @@ impl<T> Default for VecGrid<T> {
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct SparseGrid<T> {
-    cells: HashMap<(isize, isize), T>,
+    cells: FastMap<(isize, isize), T>,
+    bounds: Option<((isize, isize), (isize, isize))>,
 }
 
 impl<T> SparseGrid<T> {
     pub fn new() -> Self {
         SparseGrid {
-            cells: HashMap::new(),
+            cells: FastMap::default(),
+            bounds: None,
         }
     }
 
-    fn key_range<F>(&self, key_fn: F) -> usize
+    /// The top-left corner of the bounding box of all cells
+    pub fn min(&self) -> Option<(isize, isize)> {
+        self.bounds.map(|(min, _)| min)
+    }
+
+    /// The bottom-right corner of the bounding box of all cells
+    pub fn max(&self) -> Option<(isize, isize)> {
+        self.bounds.map(|(_, max)| max)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn remove(&mut self, coord: &(isize, isize)) -> Option<T> {
+        let removed = self.cells.remove(coord)?;
+        if let Some((min, max)) = self.bounds {
+            let on_edge =
+                coord.0 == min.0 || coord.0 == max.0 || coord.1 == min.1 || coord.1 == max.1;
+            if on_edge {
+                self.recompute_bounds();
+            }
+        }
+        Some(removed)
+    }
+
+    fn recompute_bounds(&mut self) {
+        self.bounds = self.cells.keys().fold(None, |bounds, k| match bounds {
+            None => Some((*k, *k)),
+            Some((min, max)) => Some((min.min_each(k), max.max_each(k))),
+        });
+    }
+
+    /// Every coordinate in the bounding box, row by row, with the value there if there is one
+    pub fn iter_row_major(&self) -> impl Iterator<Item = ((isize, isize), Option<&T>)> + '_ {
+        self.bounds.into_iter().flat_map(move |(min, max)| {
+            (min.1..=max.1)
+                .flat_map(move |y| (min.0..=max.0).map(move |x| ((x, y), self.cells.get(&(x, y)))))
+        })
+    }
+
+    /// Copies the cells into a dense grid covering the bounding box, returning the grid along with
+    /// the sparse coordinate of its `(0, 0)`. Missing cells are filled with the default value.
+    pub fn to_dense(&self) -> (SingleVecGrid<T>, (isize, isize))
     where
-        F: Fn(isize, isize) -> isize,
+        T: Default + Clone,
     {
-        if self.cells.is_empty() {
-            0
-        } else {
-            let mut min = isize::MAX;
-            let mut max = isize::MIN;
-            for k in self.cells.keys() {
-                let v = key_fn(k.0, k.1);
-                min = cmp::min(min, v);
-                max = cmp::max(max, v);
-            }
-            (max - min + 1) as usize
+        let offset = self.min().unwrap_or((0, 0));
+        let mut dense = SingleVecGrid::new(self.width(), self.height());
+        for (coord, value) in &self.cells {
+            let x = (coord.0 - offset.0) as usize;
+            let y = (coord.1 - offset.1) as usize;
+            dense.set((x, y), value.clone());
         }
+        (dense, offset)
     }
 }
 
@@ -562,19 +1169,31 @@ impl<T> Grid for SparseGrid<T> {
     type Coordinate = (isize, isize);
 
     fn height(&self) -> usize {
-        self.key_range(|_, y| y)
+        self.bounds
+            .map_or(0, |(min, max)| (max.1 - min.1 + 1) as usize)
     }
 
     fn width(&self) -> usize {
-        self.key_range(|x, _| x)
+        self.bounds
+            .map_or(0, |(min, max)| (max.0 - min.0 + 1) as usize)
     }
 
     fn at(&self, coord: &Self::Coordinate) -> Option<&T> {
         self.cells.get(coord)
     }
 
+    fn in_bounds(&self, coord: &Self::Coordinate) -> bool {
+        self.bounds.is_some_and(|(min, max)| {
+            (min.0..=max.0).contains(&coord.0) && (min.1..=max.1).contains(&coord.1)
+        })
+    }
+
     fn set(&mut self, coord: Self::Coordinate, val: T) {
         self.cells.insert(coord, val);
+        self.bounds = Some(match self.bounds {
+            None => (coord, coord),
+            Some((min, max)) => (min.min_each(&coord), max.max_each(&coord)),
+        });
     }
 
     fn points(&self) -> Vec<(Self::Coordinate, &Self::Value)> {
@@ -598,6 +1217,21 @@ impl<T> Default for SparseGrid<T> {
     }
 }
 
+impl<T> Hash for SparseGrid<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Map iteration order is arbitrary, so combine the cells order-independently
+        let combined = self
+            .cells
+            .iter()
+            .fold(0u128, |acc, cell| acc.wrapping_add(cell.fingerprint()));
+        self.cells.len().hash(state);
+        combined.hash(state);
+    }
+}
+
 impl<T> Clone for SparseGrid<T>
 where
     T: Clone,
@@ -605,6 +1239,7 @@ where
     fn clone(&self) -> Self {
         SparseGrid {
             cells: self.cells.clone(),
+            bounds: self.bounds,
         }
     }
 }
@@ -683,4 +1318,381 @@ mod tests {
         assert_eq!(grid.at(&(1, 2)), Some(&4));
         assert_eq!(grid.at(&(4, 2)), None);
     }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = VecGrid::from_rows(vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]]);
+
+        assert_eq!(grid.row(1).copied().collect::<Vec<_>>(), vec![4, 5, 6, 7]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![2, 6, 10]);
+        assert_eq!(grid.row(3).count(), 0);
+        assert_eq!(grid.column(4).count(), 0);
+
+        let rows = grid
+            .rows()
+            .map(|r| r.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]]
+        );
+        let columns = grid.columns().map(|c| c.sum::<i32>()).collect::<Vec<_>>();
+        assert_eq!(columns, vec![12, 15, 18, 21]);
+
+        assert_eq!(grid.rows_where(|v| *v >= 4), vec![1, 2]);
+        assert_eq!(grid.columns_where(|v| v % 2 == 1), vec![1, 3]);
+    }
+
+    #[test]
+    fn bitgrid_rows_and_columns_where() {
+        /*
+         * # . . #
+         * . . . .
+         * # . . #
+         */
+        let mut grid = BitGrid::new(4, 3);
+        grid.set((0, 0), true);
+        grid.set((3, 0), true);
+        grid.set((0, 2), true);
+        grid.set((3, 2), true);
+
+        assert_eq!(grid.rows_where(|v| !v), vec![1]);
+        assert_eq!(grid.columns_where(|v| !v), vec![1, 2]);
+        assert_eq!(grid.columns_where(|v| *v), Vec::<usize>::new());
+        assert_eq!(grid.rows_where(|_| true), vec![0, 1, 2]);
+        assert_eq!(
+            grid.row(0).copied().collect::<Vec<_>>(),
+            vec![true, false, false, true]
+        );
+        assert_eq!(
+            grid.column(3).copied().collect::<Vec<_>>(),
+            vec![true, false, true]
+        );
+
+        grid.set((1, 1), true);
+        grid.set((1, 0), true);
+        grid.set((1, 2), true);
+        assert_eq!(grid.columns_where(|v| *v), vec![1]);
+
+        // The bit slice versions agree with the generic ones
+        let generic_rows = Grid::rows(&grid)
+            .map(|r| r.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let rows = grid
+            .rows()
+            .map(|r| r.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(rows, generic_rows);
+        let generic_columns = Grid::columns(&grid)
+            .map(|c| c.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let columns = grid
+            .columns()
+            .map(|c| c.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(columns, generic_columns);
+        assert_eq!(grid.columns().len(), 4);
+        assert_eq!(grid.column(1).len(), 3);
+        assert_eq!(grid.row(3).count(), 0);
+        assert_eq!(grid.column(4).count(), 0);
+    }
+
+    fn numbered_grid() -> SingleVecGrid<usize> {
+        /*
+         *  0  1  2  3
+         *  4  5  6  7
+         *  8  9 10 11
+         */
+        let mut grid = SingleVecGrid::new(4, 3);
+        for y in 0..3 {
+            for x in 0..4 {
+                grid.set((x, y), y * 4 + x);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn directional_iterators() {
+        let grid = numbered_grid();
+        let values =
+            |it: RaycastIterator<'_, SingleVecGrid<usize>>| it.map(|(_, v)| *v).collect::<Vec<_>>();
+
+        assert_eq!(values(grid.north_from((1, 2))), vec![5, 1]);
+        assert_eq!(values(grid.south_from((1, 0))), vec![5, 9]);
+        assert_eq!(values(grid.west_from((2, 1))), vec![5, 4]);
+        assert_eq!(values(grid.east_from((1, 1))), vec![6, 7]);
+        assert_eq!(values(grid.north_from((1, 0))), Vec::<usize>::new());
+        assert_eq!(
+            grid.east_from((1, 1)).collect::<Vec<_>>(),
+            vec![((2, 1), &6), ((3, 1), &7)]
+        );
+    }
+
+    #[test]
+    fn raycast_options() {
+        let grid = numbered_grid();
+
+        assert_eq!(
+            grid.raycast((0, 0), Direction::SouthEast)
+                .include_origin()
+                .collect::<Vec<_>>(),
+            vec![((0, 0), &0), ((1, 1), &5), ((2, 2), &10)]
+        );
+        assert_eq!(
+            grid.raycast((3, 0), Direction::SouthWest)
+                .map(|(c, _)| c)
+                .collect::<Vec<_>>(),
+            vec![(2, 1), (1, 2)]
+        );
+        assert_eq!(
+            grid.raycast((0, 1), Direction::East)
+                .until(|_, v| v % 2 == 0)
+                .collect::<Vec<_>>(),
+            vec![((1, 1), &5), ((2, 1), &6)]
+        );
+        assert_eq!(
+            grid.raycast((0, 0), (2, 1))
+                .include_origin()
+                .map(|(_, v)| *v)
+                .collect::<Vec<_>>(),
+            vec![0, 6]
+        );
+
+        let mut sparse = SparseGrid::new();
+        for x in -3..=0 {
+            sparse.set((x, -x), x);
+        }
+        assert_eq!(
+            sparse
+                .raycast((0, 0), Direction::SouthWest)
+                .map(|(_, v)| *v)
+                .collect::<Vec<_>>(),
+            vec![-1, -2, -3]
+        );
+    }
+
+    #[test]
+    fn sparse_raycast_skips_empty_cells() {
+        let mut sparse = SparseGrid::new();
+        sparse.set((0, 0), 'a');
+        sparse.set((3, 0), 'b');
+        sparse.set((5, 2), 'c');
+        sparse.set((-1, 4), 'd');
+
+        // Rays carry on over empty cells to the edge of the bounding box
+        assert_eq!(
+            sparse.east_from((0, 0)).collect::<Vec<_>>(),
+            vec![((3, 0), &'b')]
+        );
+        assert_eq!(
+            sparse.south_from((5, 0)).collect::<Vec<_>>(),
+            vec![((5, 2), &'c')]
+        );
+        assert_eq!(
+            sparse
+                .raycast((-1, 0), Direction::East)
+                .include_origin()
+                .map(|(_, v)| *v)
+                .collect::<String>(),
+            "ab"
+        );
+        assert_eq!(
+            sparse
+                .west_from((5, 0))
+                .until(|_, v| *v == 'b')
+                .collect::<Vec<_>>(),
+            vec![((3, 0), &'b')]
+        );
+        assert_eq!(sparse.north_from((-1, 4)).count(), 0);
+
+        assert!(sparse.in_bounds(&(5, 4)));
+        assert!(!sparse.in_bounds(&(6, 0)));
+        assert!(!SparseGrid::<char>::new().in_bounds(&(0, 0)));
+    }
+
+    #[test]
+    fn resizing_bitgrid_grows_in_all_directions() {
+        let mut grid = ResizingBitGrid::new(2, 2);
+        grid.set((1, 1), true);
+        grid.set((-3, 0), true);
+        grid.set((0, -5), true);
+        grid.set((7, 9), true);
+        grid.set((-100, -100), false);
+
+        assert_eq!(grid.set_cell_count(), 4);
+        assert!(grid.origin().0 <= -3 && grid.origin().1 <= -5);
+        let max = grid.max().unwrap();
+        assert!(max.0 >= 7 && max.1 >= 9);
+        for coord in [(1, 1), (-3, 0), (0, -5), (7, 9)] {
+            assert_eq!(grid.at(&coord), Some(&true));
+        }
+        assert_eq!(grid.at(&(0, 0)), Some(&false));
+        assert_eq!(grid.at(&(-100, -100)), None);
+
+        let mut set = grid.set_cells().collect::<Vec<_>>();
+        set.sort();
+        assert_eq!(set, vec![(-3, 0), (0, -5), (1, 1), (7, 9)]);
+    }
+
+    #[test]
+    fn resizing_bitgrid_from_rows_keeps_unset_cells() {
+        let grid = ResizingBitGrid::from_rows(vec![
+            vec![false, false, false],
+            vec![false, true, false],
+            vec![false, false, true],
+        ]);
+        assert_eq!(grid.origin(), (0, 0));
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.at(&(0, 0)), Some(&false));
+        assert_eq!(grid.at(&(1, 1)), Some(&true));
+        assert_eq!(grid.at(&(2, 2)), Some(&true));
+        assert_eq!(grid.set_cell_count(), 2);
+
+        // Short rows are padded out with unset cells
+        let ragged = ResizingBitGrid::from_rows(vec![vec![true], vec![false, false, true]]);
+        assert_eq!((ragged.width(), ragged.height()), (3, 2));
+        assert_eq!(ragged.at(&(2, 0)), Some(&false));
+        assert_eq!(ragged.at(&(2, 1)), Some(&true));
+    }
+
+    #[test]
+    fn resizing_bitgrid_growth_is_geometric() {
+        let mut grid = ResizingBitGrid::new(0, 0);
+        let mut resizes = 0;
+        let mut width = grid.width();
+        for x in 0..1000 {
+            grid.set((-x, 0), true);
+            if grid.width() != width {
+                resizes += 1;
+                width = grid.width();
+            }
+        }
+        assert!(resizes <= 11, "{} resizes", resizes);
+        assert_eq!(grid.set_cell_count(), 1000);
+    }
+
+    #[test]
+    fn resizing_bitgrid_shrink_to_fit() {
+        let mut grid = ResizingBitGrid::with_origin((-10, -10), 30, 30);
+        grid.set((-2, 3), true);
+        grid.set((4, -1), true);
+        grid.set((0, 0), true);
+        grid.shrink_to_fit();
+
+        assert_eq!(grid.origin(), (-2, -1));
+        assert_eq!(grid.max(), Some((4, 3)));
+        assert_eq!(grid.width(), 7);
+        assert_eq!(grid.height(), 5);
+        assert_eq!(grid.set_cell_count(), 3);
+        assert_eq!(grid.at(&(-2, 3)), Some(&true));
+        assert_eq!(grid.at(&(4, -1)), Some(&true));
+        assert_eq!(grid.at(&(0, 0)), Some(&true));
+
+        grid.set((4, -1), false);
+        grid.set((-2, 3), false);
+        grid.set((0, 0), false);
+        grid.shrink_to_fit();
+        assert_eq!(grid.max(), None);
+
+        grid.set((5, 5), true);
+        assert_eq!(grid.at(&(5, 5)), Some(&true));
+        assert_eq!(grid.set_cell_count(), 1);
+    }
+
+    #[test]
+    fn sparsegrid_bounds_and_removal() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.min(), None);
+
+        grid.set((2, -3), 'a');
+        grid.set((-1, 0), 'b');
+        grid.set((0, 1), 'c');
+        assert_eq!(grid.min(), Some((-1, -3)));
+        assert_eq!(grid.max(), Some((2, 1)));
+
+        assert_eq!(grid.remove(&(1, 1)), None);
+        assert_eq!(grid.remove(&(2, -3)), Some('a'));
+        assert_eq!(grid.min(), Some((-1, 0)));
+        assert_eq!(grid.max(), Some((0, 1)));
+        assert_eq!(grid.width(), 2);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.len(), 2);
+
+        grid.remove(&(-1, 0));
+        grid.remove(&(0, 1));
+        assert!(grid.is_empty());
+        assert_eq!(grid.max(), None);
+        assert_eq!(grid.width(), 0);
+    }
+
+    #[test]
+    fn sparsegrid_row_major_and_dense() {
+        let mut grid = SparseGrid::new();
+        grid.set((-1, 5), 1);
+        grid.set((0, 6), 2);
+        grid.set((-1, 6), 3);
+
+        assert_eq!(
+            grid.iter_row_major().collect::<Vec<_>>(),
+            vec![
+                ((-1, 5), Some(&1)),
+                ((0, 5), None),
+                ((-1, 6), Some(&3)),
+                ((0, 6), Some(&2)),
+            ]
+        );
+
+        let (dense, offset) = grid.to_dense();
+        assert_eq!(offset, (-1, 5));
+        assert_eq!(dense.row(0).copied().collect::<Vec<_>>(), vec![1, 0]);
+        assert_eq!(dense.row(1).copied().collect::<Vec<_>>(), vec![3, 2]);
+    }
+
+    #[test]
+    fn grid_equality_and_fingerprints() {
+        let mut a = SingleVecGrid::new(3, 2);
+        let mut b = SingleVecGrid::new(3, 2);
+        a.set((1, 1), 5u8);
+        b.set((1, 1), 5u8);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        b.set((2, 0), 1);
+        assert_ne!(a.fingerprint(), b.fingerprint());
+
+        let mut a = BitGrid::new(70, 3);
+        let mut b = BitGrid::new(70, 3);
+        a.set((65, 2), true);
+        b.set((65, 2), true);
+        assert_eq!(a, b);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        b.set((0, 0), true);
+        assert_ne!(a, b);
+        assert_ne!(a.fingerprint(), b.fingerprint());
+        assert_ne!(
+            BitGrid::new(2, 3).fingerprint(),
+            BitGrid::new(3, 2).fingerprint()
+        );
+
+        let mut a = SparseGrid::new();
+        let mut b = SparseGrid::new();
+        for i in 0..50 {
+            a.set((i, -i), i);
+            b.set((49 - i, i - 49), 49 - i);
+        }
+        assert_eq!(a, b);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        b.set((0, 0), 1);
+        assert_ne!(a.fingerprint(), b.fingerprint());
+
+        let mut a = ResizingBitGrid::new(2, 2);
+        let mut b = ResizingBitGrid::with_origin((-5, -5), 20, 20);
+        for coord in [(1, 1), (-3, 4), (7, 0)] {
+            a.set(coord, true);
+            b.set(coord, true);
+        }
+        assert_eq!(a, b);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        b.set((0, 0), true);
+        assert_ne!(a, b);
+    }
 }
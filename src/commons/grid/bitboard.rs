@@ -0,0 +1,313 @@
+use super::regions::Connectivity;
+use super::{BitGrid, Direction};
+use bitvec::prelude::*;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+/// Birth and survival neighbour counts for a Life-like automaton, as bitmasks over 0..=8
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LifeRule {
+    birth: u16,
+    survive: u16,
+}
+
+impl LifeRule {
+    pub const CONWAY: LifeRule = LifeRule {
+        birth: 1 << 3,
+        survive: 1 << 2 | 1 << 3,
+    };
+
+    pub fn new(birth: &[u8], survive: &[u8]) -> Self {
+        let mask = |counts: &[u8]| counts.iter().fold(0u16, |acc, n| acc | 1 << n);
+        Self {
+            birth: mask(birth),
+            survive: mask(survive),
+        }
+    }
+}
+
+/// Per-cell neighbour counts stored as bit planes, least significant plane first
+pub struct NeighbourCounts {
+    planes: [BitVec; 4],
+    width: usize,
+    height: usize,
+}
+
+impl NeighbourCounts {
+    pub fn at(&self, coord: (usize, usize)) -> Option<u8> {
+        let (x, y) = coord;
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = y * self.width + x;
+        Some(
+            self.planes
+                .iter()
+                .enumerate()
+                .map(|(bit, plane)| (plane[i] as u8) << bit)
+                .sum(),
+        )
+    }
+
+    /// Cells with exactly `n` set neighbours
+    pub fn equal_to(&self, n: u8) -> BitGrid {
+        let mut mask = bitvec![1; self.width * self.height];
+        for (bit, plane) in self.planes.iter().enumerate() {
+            if n & (1 << bit) != 0 {
+                *mask.as_mut_bitslice() &= plane.as_bitslice();
+            } else {
+                *mask.as_mut_bitslice() &= (!plane.clone()).as_bitslice();
+            }
+        }
+        BitGrid {
+            values: mask,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl BitGrid {
+    fn assert_same_size(&self, other: &BitGrid) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "Grids must be the same size"
+        );
+    }
+
+    /// Moves every cell one step in `dir`. Cells pushed over the edge are dropped rather than
+    /// wrapping onto the next row.
+    pub fn shift(&mut self, dir: Direction) {
+        if self.values.is_empty() {
+            return;
+        }
+
+        let (dx, dy) = dir.delta();
+        match dy {
+            -1 => self.values.shift_left(self.width),
+            1 => self.values.shift_right(self.width),
+            _ => {}
+        }
+        match dx {
+            -1 => {
+                self.values.shift_left(1);
+                for y in 0..self.height {
+                    self.values.set(y * self.width + self.width - 1, false);
+                }
+            }
+            1 => {
+                self.values.shift_right(1);
+                for y in 0..self.height {
+                    self.values.set(y * self.width, false);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn shifted(&self, dir: Direction) -> BitGrid {
+        let mut grid = self.clone();
+        grid.shift(dir);
+        grid
+    }
+
+    /// Cells that neighbour at least one set cell, not including the set cells themselves unless
+    /// they also neighbour one
+    pub fn spread(&self, connectivity: Connectivity) -> BitGrid {
+        let mut spread = BitGrid::new(self.width, self.height);
+        for dir in connectivity.directions() {
+            spread |= &self.shifted(*dir);
+        }
+        spread
+    }
+
+    /// Counts the set neighbours of every cell at once, summing shifted copies of the grid with
+    /// a ripple-carry adder over whole words
+    pub fn neighbour_counts(&self, connectivity: Connectivity) -> NeighbourCounts {
+        let len = self.values.len();
+        let mut planes: [BitVec; 4] = std::array::from_fn(|_| bitvec![0; len]);
+        for dir in connectivity.directions() {
+            let mut carry = self.shifted(*dir).values;
+            for plane in planes.iter_mut() {
+                if carry.not_any() {
+                    break;
+                }
+                let mut next_carry = plane.clone();
+                *next_carry.as_mut_bitslice() &= carry.as_bitslice();
+                *plane.as_mut_bitslice() ^= carry.as_bitslice();
+                carry = next_carry;
+            }
+        }
+        NeighbourCounts {
+            planes,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn life_step(&self, rule: LifeRule, connectivity: Connectivity) -> BitGrid {
+        let counts = self.neighbour_counts(connectivity);
+        let mut next = BitGrid::new(self.width, self.height);
+        for n in 0..=8u8 {
+            let born = rule.birth & (1 << n) != 0;
+            let survives = rule.survive & (1 << n) != 0;
+            if !born && !survives {
+                continue;
+            }
+            let mut cells = counts.equal_to(n);
+            if !born {
+                cells &= self;
+            } else if !survives {
+                cells &= &!self;
+            }
+            next |= &cells;
+        }
+        next
+    }
+}
+
+macro_rules! bitgrid_op {
+    ($op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident) => {
+        impl $assign<&BitGrid> for BitGrid {
+            fn $assign_fn(&mut self, rhs: &BitGrid) {
+                self.assert_same_size(rhs);
+                self.values
+                    .as_mut_bitslice()
+                    .$assign_fn(rhs.values.as_bitslice());
+            }
+        }
+
+        impl $op<&BitGrid> for BitGrid {
+            type Output = BitGrid;
+
+            fn $op_fn(mut self, rhs: &BitGrid) -> BitGrid {
+                self.$assign_fn(rhs);
+                self
+            }
+        }
+
+        impl $op<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+
+            fn $op_fn(self, rhs: &BitGrid) -> BitGrid {
+                self.clone().$op_fn(rhs)
+            }
+        }
+    };
+}
+
+bitgrid_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+bitgrid_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+bitgrid_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Not for BitGrid {
+    type Output = BitGrid;
+
+    fn not(mut self) -> BitGrid {
+        self.values = !self.values;
+        self
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        !self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commons::grid::Grid;
+
+    fn parse(rows: &[&str]) -> BitGrid {
+        BitGrid::from_rows(rows.iter().map(|r| r.chars().map(|c| c == '#')))
+    }
+
+    fn cells(grid: &BitGrid) -> Vec<(usize, usize)> {
+        grid.set_cells().collect()
+    }
+
+    #[test]
+    fn bitwise_ops() {
+        let a = parse(&["##..", "#..."]);
+        let b = parse(&[".#.#", "#..#"]);
+
+        assert_eq!(cells(&(&a & &b)), vec![(1, 0), (0, 1)]);
+        assert_eq!(
+            cells(&(&a | &b)),
+            vec![(0, 0), (1, 0), (3, 0), (0, 1), (3, 1)]
+        );
+        assert_eq!(cells(&(&a ^ &b)), vec![(0, 0), (3, 0), (3, 1)]);
+        assert_eq!(cells(&!&a), vec![(2, 0), (3, 0), (1, 1), (2, 1), (3, 1)]);
+        assert_eq!((!a).set_cell_count(), 5);
+    }
+
+    #[test]
+    fn shifts_mask_edges() {
+        let grid = parse(&["#..#", "....", "#..#"]);
+
+        assert_eq!(cells(&grid.shifted(Direction::East)), vec![(1, 0), (1, 2)]);
+        assert_eq!(cells(&grid.shifted(Direction::West)), vec![(2, 0), (2, 2)]);
+        assert_eq!(cells(&grid.shifted(Direction::North)), vec![(0, 1), (3, 1)]);
+        assert_eq!(cells(&grid.shifted(Direction::South)), vec![(0, 1), (3, 1)]);
+        assert_eq!(cells(&grid.shifted(Direction::SouthEast)), vec![(1, 1)]);
+        assert_eq!(cells(&grid.shifted(Direction::NorthWest)), vec![(2, 1)]);
+    }
+
+    #[test]
+    fn neighbour_counts() {
+        let grid = parse(&["###", "###", "###"]);
+
+        let eight = grid.neighbour_counts(Connectivity::Eight);
+        assert_eq!(eight.at((1, 1)), Some(8));
+        assert_eq!(eight.at((0, 0)), Some(3));
+        assert_eq!(eight.at((1, 0)), Some(5));
+        assert_eq!(eight.at((3, 0)), None);
+        assert_eq!(
+            cells(&eight.equal_to(5)),
+            vec![(1, 0), (0, 1), (2, 1), (1, 2)]
+        );
+
+        let four = grid.neighbour_counts(Connectivity::Four);
+        assert_eq!(four.at((1, 1)), Some(4));
+        assert_eq!(four.at((0, 0)), Some(2));
+    }
+
+    #[test]
+    fn life_blinker() {
+        let grid = parse(&[".....", ".....", ".###.", ".....", "....."]);
+
+        let next = grid.life_step(LifeRule::CONWAY, Connectivity::Eight);
+        assert_eq!(cells(&next), vec![(2, 1), (2, 2), (2, 3)]);
+        let next = next.life_step(LifeRule::new(&[3], &[2, 3]), Connectivity::Eight);
+        assert_eq!(cells(&next), cells(&grid));
+    }
+
+    #[test]
+    fn reachable_after_steps() {
+        let garden = parse(&[
+            "...........",
+            ".....###.#.",
+            ".###.##..#.",
+            "..#.#...#..",
+            "....#.#....",
+            ".##...####.",
+            ".##..#...#.",
+            ".......##..",
+            ".##.#.####.",
+            ".##..##.##.",
+            "...........",
+        ]);
+        let open = !&garden;
+
+        let mut reach = BitGrid::new(garden.width(), garden.height());
+        reach.set((5, 5), true);
+        for _ in 0..6 {
+            reach = reach.spread(Connectivity::Four) & &open;
+        }
+        assert_eq!(reach.set_cell_count(), 16);
+    }
+}
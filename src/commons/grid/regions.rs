@@ -0,0 +1,277 @@
+use super::{Direction, Grid, GridCoordinate};
+use crate::commons::hash::{FastMap, FastSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+impl Connectivity {
+    pub fn directions(&self) -> &'static [Direction] {
+        match self {
+            Self::Four => &Direction::CARDINAL,
+            Self::Eight => &Direction::ALL,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region<C> {
+    /// The first cell found in the region, useful for looking up what the region is made of
+    pub seed: C,
+    pub size: usize,
+    pub min: C,
+    pub max: C,
+    /// Number of cell edges bordering a cell outside the region (or the outside of the grid).
+    /// Only the four cardinal edges of each cell count, whatever the connectivity.
+    pub perimeter: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Components<C> {
+    pub labels: FastMap<C, usize>,
+    pub regions: Vec<Region<C>>,
+}
+
+impl<C> Components<C>
+where
+    C: GridCoordinate,
+{
+    pub fn label(&self, coord: &C) -> Option<usize> {
+        self.labels.get(coord).copied()
+    }
+
+    pub fn region_of(&self, coord: &C) -> Option<&Region<C>> {
+        self.label(coord).map(|l| &self.regions[l])
+    }
+}
+
+/// Walks every cell reachable from `seed`, calling `visit` once per cell. `same_region` is given
+/// the value of the cell being expanded and the value of its neighbour.
+fn fill_from<G, F, V>(
+    grid: &G,
+    seed: G::Coordinate,
+    connectivity: Connectivity,
+    same_region: &F,
+    visited: &mut V,
+    mut visit: impl FnMut(G::Coordinate),
+) where
+    G: Grid,
+    F: Fn(&G::Value, &G::Value) -> bool,
+    V: FnMut(G::Coordinate) -> bool,
+{
+    if grid.at(&seed).is_none() || !visited(seed) {
+        return;
+    }
+
+    let mut stack = vec![seed];
+    while let Some(coord) = stack.pop() {
+        visit(coord);
+        let value = grid.at(&coord).unwrap();
+        for dir in connectivity.directions() {
+            let next = match coord.offset(dir.delta()) {
+                Some(next) => next,
+                None => continue,
+            };
+            if let Some(next_value) = grid.at(&next) {
+                if same_region(value, next_value) && visited(next) {
+                    stack.push(next);
+                }
+            }
+        }
+    }
+}
+
+/// All cells connected to `seed`, in no particular order
+pub fn flood_fill<G, F>(
+    grid: &G,
+    seed: G::Coordinate,
+    connectivity: Connectivity,
+    same_region: F,
+) -> Vec<G::Coordinate>
+where
+    G: Grid,
+    F: Fn(&G::Value, &G::Value) -> bool,
+{
+    let mut seen = FastSet::default();
+    let mut filled = Vec::new();
+    fill_from(
+        grid,
+        seed,
+        connectivity,
+        &same_region,
+        &mut |c| seen.insert(c),
+        |c| filled.push(c),
+    );
+    filled
+}
+
+/// Splits the whole grid into connected regions. Labels are assigned in the order the grid's
+/// `points` are returned, so are only stable for grids with a stable point order.
+pub fn label_components<G, F>(
+    grid: &G,
+    connectivity: Connectivity,
+    same_region: F,
+) -> Components<G::Coordinate>
+where
+    G: Grid,
+    F: Fn(&G::Value, &G::Value) -> bool,
+{
+    let mut labels = FastMap::default();
+    let mut regions = Vec::new();
+
+    for (seed, _) in grid.points() {
+        if labels.contains_key(&seed) {
+            continue;
+        }
+
+        let label = regions.len();
+        let mut region = Region {
+            seed,
+            size: 0,
+            min: seed,
+            max: seed,
+            perimeter: 0,
+        };
+        let mut cells = Vec::new();
+        fill_from(
+            grid,
+            seed,
+            connectivity,
+            &same_region,
+            &mut |c| labels.insert(c, label).is_none(),
+            |c| cells.push(c),
+        );
+
+        for coord in cells {
+            region.size += 1;
+            region.min = region.min.min_each(&coord);
+            region.max = region.max.max_each(&coord);
+            region.perimeter += Direction::CARDINAL
+                .iter()
+                .filter(|dir| {
+                    coord.offset(dir.delta()).and_then(|n| labels.get(&n)) != Some(&label)
+                })
+                .count();
+        }
+        regions.push(region);
+    }
+
+    Components { labels, regions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commons::grid::{BitGrid, SingleVecGrid, SparseGrid};
+
+    fn letter_grid(rows: &[&str]) -> SingleVecGrid<char> {
+        let mut grid = SingleVecGrid::new(rows[0].len(), rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid.set((x, y), c);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn flood_fill_singlevecgrid() {
+        let grid = letter_grid(&["AAAA", "BBCD", "BBCC", "EEEC"]);
+
+        let mut filled = flood_fill(&grid, (2, 1), Connectivity::Four, |a, b| a == b);
+        filled.sort();
+        assert_eq!(filled, vec![(2, 1), (2, 2), (3, 2), (3, 3)]);
+
+        // D only touches the C region diagonally, so 8-connectivity joins C and D when allowed to
+        let filled = flood_fill(&grid, (3, 1), Connectivity::Eight, |_, b| *b != 'A');
+        assert_eq!(filled.len(), 12);
+    }
+
+    #[test]
+    fn label_singlevecgrid() {
+        let grid = letter_grid(&["AAAA", "BBCD", "BBCC", "EEEC"]);
+        let components = label_components(&grid, Connectivity::Four, |a, b| a == b);
+
+        assert_eq!(components.regions.len(), 5);
+        let summary = components
+            .regions
+            .iter()
+            .map(|r| {
+                (
+                    *grid.at(&r.seed).unwrap(),
+                    r.size,
+                    r.perimeter,
+                    r.min,
+                    r.max,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ('A', 4, 10, (0, 0), (3, 0)),
+                ('B', 4, 8, (0, 1), (1, 2)),
+                ('C', 4, 10, (2, 1), (3, 3)),
+                ('D', 1, 4, (3, 1), (3, 1)),
+                ('E', 3, 8, (0, 3), (2, 3)),
+            ]
+        );
+        assert_eq!(components.label(&(3, 3)), components.label(&(2, 1)));
+        assert_ne!(components.label(&(3, 1)), components.label(&(2, 1)));
+    }
+
+    #[test]
+    fn label_bitgrid() {
+        /*
+         * # . #
+         * . # .
+         * . . #
+         */
+        let mut grid = BitGrid::new(3, 3);
+        grid.set((0, 0), true);
+        grid.set((2, 0), true);
+        grid.set((1, 1), true);
+        grid.set((2, 2), true);
+
+        let four = label_components(&grid, Connectivity::Four, |a, b| a == b);
+        let set_regions = four
+            .regions
+            .iter()
+            .filter(|r| *grid.at(&r.seed).unwrap())
+            .count();
+        assert_eq!(set_regions, 4);
+
+        let eight = label_components(&grid, Connectivity::Eight, |a, b| a == b);
+        let set_regions = eight
+            .regions
+            .iter()
+            .filter(|r| *grid.at(&r.seed).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(set_regions.len(), 1);
+        assert_eq!(set_regions[0].size, 4);
+        assert_eq!(set_regions[0].perimeter, 16);
+        assert_eq!((set_regions[0].min, set_regions[0].max), ((0, 0), (2, 2)));
+    }
+
+    #[test]
+    fn label_sparsegrid() {
+        let mut grid = SparseGrid::new();
+        grid.set((-5, -5), 1);
+        grid.set((-5, -4), 1);
+        grid.set((-4, -4), 2);
+        grid.set((10, 10), 1);
+
+        let components = label_components(&grid, Connectivity::Four, |a, b| a == b);
+        assert_eq!(components.regions.len(), 3);
+        let region = components.region_of(&(-5, -5)).unwrap();
+        assert_eq!(region.size, 2);
+        assert_eq!(region.perimeter, 6);
+        assert_eq!((region.min, region.max), ((-5, -5), (-5, -4)));
+
+        let filled = flood_fill(&grid, (-5, -5), Connectivity::Four, |_, _| true);
+        assert_eq!(filled.len(), 3);
+        assert!(flood_fill(&grid, (0, 0), Connectivity::Four, |_, _| true).is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+use super::{Grid, SingleVecGrid};
+
+/// Builds a grid `factor` times larger in each direction, where each original cell becomes a
+/// `factor`×`factor` block. `f` is called for every cell of every block with the original value
+/// and the `(x, y)` offset within the block.
+pub fn upscale<G, U, F>(grid: &G, factor: usize, f: F) -> SingleVecGrid<U>
+where
+    G: Grid<Coordinate = (usize, usize)>,
+    U: Default + Clone,
+    F: Fn(&G::Value, (usize, usize)) -> U,
+{
+    let mut scaled = SingleVecGrid::new(grid.width() * factor, grid.height() * factor);
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let value = grid.at(&(x, y)).unwrap();
+            for dy in 0..factor {
+                for dx in 0..factor {
+                    scaled.set((x * factor + dx, y * factor + dy), f(value, (dx, dy)));
+                }
+            }
+        }
+    }
+    scaled
+}
+
+/// The top-left cell of the block an original coordinate was scaled to
+pub fn project_up(coord: (usize, usize), factor: usize) -> (usize, usize) {
+    (coord.0 * factor, coord.1 * factor)
+}
+
+/// The original coordinate a cell of an upscaled grid came from, or `None` for a `factor` of 0
+pub fn project_down(coord: (usize, usize), factor: usize) -> Option<(usize, usize)> {
+    Some((coord.0.checked_div(factor)?, coord.1.checked_div(factor)?))
+}
+
+/// Where in its block a cell of an upscaled grid is, or `None` for a `factor` of 0
+pub fn block_offset(coord: (usize, usize), factor: usize) -> Option<(usize, usize)> {
+    Some((coord.0.checked_rem(factor)?, coord.1.checked_rem(factor)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commons::grid::regions::{flood_fill, Connectivity};
+    use crate::commons::grid::VecGrid;
+
+    #[test]
+    fn upscale_blocks() {
+        let grid = VecGrid::from_rows(vec![vec![1, 2], vec![3, 4]]);
+        let scaled = upscale(&grid, 2, |v, (dx, dy)| v * 10 + dx + dy * 2);
+
+        assert_eq!(scaled.width(), 4);
+        assert_eq!(scaled.height(), 4);
+        assert_eq!(
+            scaled.row(0).copied().collect::<Vec<_>>(),
+            vec![10, 11, 20, 21]
+        );
+        assert_eq!(
+            scaled.row(3).copied().collect::<Vec<_>>(),
+            vec![32, 33, 42, 43]
+        );
+
+        assert_eq!(project_up((1, 1), 2), (2, 2));
+        assert_eq!(project_down((3, 2), 2), Some((1, 1)));
+        assert_eq!(block_offset((3, 2), 2), Some((1, 0)));
+        assert_eq!(project_down((3, 2), 0), None);
+        assert_eq!(block_offset((3, 2), 0), None);
+    }
+
+    #[test]
+    fn squeeze_between_pipes() {
+        let maze = [
+            "..........",
+            ".F------7.",
+            ".|F----7|.",
+            ".||....||.",
+            ".||....||.",
+            ".|L-7F-J|.",
+            ".|..||..|.",
+            ".L--JL--J.",
+            "..........",
+        ];
+        let grid = VecGrid::from_rows(maze.iter().map(|l| l.chars()));
+
+        let walls = upscale(&grid, 3, |c, offset| match (c, offset) {
+            ('.', _) => false,
+            (_, (1, 1)) => true,
+            ('|' | 'L' | 'J', (1, 0)) => true,
+            ('|' | '7' | 'F', (1, 2)) => true,
+            ('-' | 'J' | '7', (0, 1)) => true,
+            ('-' | 'L' | 'F', (2, 1)) => true,
+            _ => false,
+        });
+
+        let outside = flood_fill(&walls, (0, 0), Connectivity::Four, |_, wall| !wall);
+        let mut reached = VecGrid::from_rows(maze.iter().map(|l| l.chars().map(|_| false)));
+        for coord in outside {
+            if block_offset(coord, 3) == Some((1, 1)) {
+                reached.set(project_down(coord, 3).unwrap(), true);
+            }
+        }
+
+        let enclosed = grid
+            .points()
+            .into_iter()
+            .filter(|(coord, c)| **c == '.' && !reached.at(coord).unwrap())
+            .count();
+        assert_eq!(enclosed, 4);
+    }
+}
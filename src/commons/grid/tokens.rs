@@ -0,0 +1,207 @@
+use super::{Direction, Grid, GridCoordinate};
+use crate::commons::hash::{FastMap, FastSet};
+use std::ops::Range;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TokenError {
+    #[error("Number starting at {0:?} is too big")]
+    TooBig((usize, usize)),
+}
+
+/// A run of digits along a row
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NumberToken {
+    pub value: u64,
+    pub y: usize,
+    pub xs: Range<usize>,
+}
+
+impl NumberToken {
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.xs.clone().map(|x| (x, self.y))
+    }
+
+    /// Whether `cell` is next to the token, diagonals included, without being part of it
+    pub fn is_adjacent(&self, (x, y): (usize, usize)) -> bool {
+        let near_row = y + 1 >= self.y && y <= self.y + 1;
+        let near_column = x + 1 >= self.xs.start && x <= self.xs.end;
+        near_row && near_column && !(y == self.y && self.xs.contains(&x))
+    }
+}
+
+/// Every number in a grid of characters, indexed by the cells they cover
+#[derive(Clone, Debug, Default)]
+pub struct NumberTokens {
+    tokens: Vec<NumberToken>,
+    cells: FastMap<(usize, usize), usize>,
+}
+
+impl NumberTokens {
+    pub fn scan<G>(grid: &G) -> Result<Self, TokenError>
+    where
+        G: Grid<Coordinate = (usize, usize), Value = char>,
+    {
+        let mut found = Self::default();
+        for y in 0..grid.height() {
+            let mut current: Option<NumberToken> = None;
+            for x in 0..=grid.width() {
+                let digit = grid.at(&(x, y)).and_then(|c| c.to_digit(10));
+                match (digit, current.as_mut()) {
+                    (Some(d), Some(token)) => {
+                        token.value = token
+                            .value
+                            .checked_mul(10)
+                            .and_then(|v| v.checked_add(d as u64))
+                            .ok_or(TokenError::TooBig((token.xs.start, y)))?;
+                        token.xs.end = x + 1;
+                    }
+                    (Some(d), None) => {
+                        current = Some(NumberToken {
+                            value: d as u64,
+                            y,
+                            xs: x..x + 1,
+                        })
+                    }
+                    (None, _) => {
+                        if let Some(token) = current.take() {
+                            found.push(token);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    fn push(&mut self, token: NumberToken) {
+        let i = self.tokens.len();
+        self.cells.extend(token.cells().map(|c| (c, i)));
+        self.tokens.push(token);
+    }
+
+    pub fn tokens(&self) -> &[NumberToken] {
+        &self.tokens
+    }
+
+    /// The token covering `cell`, if any
+    pub fn at(&self, cell: (usize, usize)) -> Option<&NumberToken> {
+        self.cells.get(&cell).map(|&i| &self.tokens[i])
+    }
+
+    fn adjacent_indices(&self, cell: (usize, usize)) -> impl Iterator<Item = usize> + '_ {
+        Direction::ALL
+            .iter()
+            .filter_map(move |d| cell.offset(d.delta()))
+            .filter_map(|c| self.cells.get(&c).copied())
+    }
+
+    /// Distinct tokens touching `cell`, diagonals included, in the order they were scanned
+    pub fn adjacent_to(&self, cell: (usize, usize)) -> Vec<&NumberToken> {
+        self.adjacent_to_any([cell])
+    }
+
+    /// Distinct tokens touching any of `cells`, in the order they were scanned
+    pub fn adjacent_to_any(
+        &self,
+        cells: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Vec<&NumberToken> {
+        let mut indices = cells
+            .into_iter()
+            .flat_map(|c| self.adjacent_indices(c))
+            .collect::<FastSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices.into_iter().map(|i| &self.tokens[i]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commons::grid::SingleVecGrid;
+
+    const EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    fn grid(s: &str) -> SingleVecGrid<char> {
+        SingleVecGrid::from_rows(s.lines().map(str::chars))
+    }
+
+    #[test]
+    fn scans_numbers() {
+        let tokens = NumberTokens::scan(&grid(EXAMPLE)).unwrap();
+        assert_eq!(tokens.tokens().len(), 10);
+        assert_eq!(
+            tokens.tokens()[1],
+            NumberToken {
+                value: 114,
+                y: 0,
+                xs: 5..8
+            }
+        );
+        assert_eq!(tokens.at((7, 2)).map(|t| t.value), Some(633));
+        assert_eq!(tokens.at((3, 0)), None);
+
+        // Numbers running into the right edge are still found
+        let edge = NumberTokens::scan(&grid("..12\n3..4")).unwrap();
+        let values = edge.tokens().iter().map(|t| t.value).collect::<Vec<_>>();
+        assert_eq!(values, [12, 3, 4]);
+
+        let long = grid(&"9".repeat(25));
+        assert_eq!(
+            NumberTokens::scan(&long).unwrap_err(),
+            TokenError::TooBig((0, 0))
+        );
+    }
+
+    #[test]
+    fn part_numbers_and_gears() {
+        let grid = grid(EXAMPLE);
+        let tokens = NumberTokens::scan(&grid).unwrap();
+        let symbols = grid
+            .points()
+            .into_iter()
+            .filter(|(_, c)| !c.is_ascii_digit() && **c != '.')
+            .map(|(cell, _)| cell)
+            .collect::<Vec<_>>();
+
+        let parts = tokens.adjacent_to_any(symbols.iter().copied());
+        assert_eq!(parts.iter().map(|t| t.value).sum::<u64>(), 4361);
+        assert!(parts
+            .iter()
+            .all(|t| symbols.iter().any(|&s| t.is_adjacent(s))));
+
+        let gear_ratios = symbols
+            .iter()
+            .filter(|&&cell| grid.at(&cell) == Some(&'*'))
+            .map(|&cell| tokens.adjacent_to(cell))
+            .filter(|adjacent| adjacent.len() == 2)
+            .map(|adjacent| adjacent[0].value * adjacent[1].value)
+            .sum::<u64>();
+        assert_eq!(gear_ratios, 467835);
+    }
+
+    #[test]
+    fn adjacency() {
+        let token = NumberToken {
+            value: 35,
+            y: 2,
+            xs: 2..4,
+        };
+        assert!(token.is_adjacent((1, 1)));
+        assert!(token.is_adjacent((4, 3)));
+        assert!(!token.is_adjacent((5, 2)));
+        assert!(!token.is_adjacent((2, 2)));
+        assert!(!token.is_adjacent((3, 0)));
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// The FxHash algorithm from rustc: a multiply and rotate per word. Very fast on small keys like
+/// coordinates, but offers no protection against crafted collisions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    #[inline]
+    fn add_to_hash(&mut self, i: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ i).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for b in chunks.remainder() {
+            self.add_to_hash(*b as u64);
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type BuildFxHasher = BuildHasherDefault<FxHasher>;
+
+/// A `HashMap` using `FxHasher`. Create with `FastMap::default()` rather than `new()`.
+pub type FastMap<K, V> = HashMap<K, V, BuildFxHasher>;
+
+/// A `HashSet` using `FxHasher`. Create with `FastSet::default()` rather than `new()`.
+pub type FastSet<T> = HashSet<T, BuildFxHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasher, Hash};
+
+    fn hash<T: Hash>(v: T) -> u64 {
+        BuildFxHasher::default().hash_one(v)
+    }
+
+    #[test]
+    fn fxhash_is_deterministic() {
+        assert_eq!(hash((1usize, 2usize)), hash((1usize, 2usize)));
+        assert_ne!(hash((1usize, 2usize)), hash((2usize, 1usize)));
+        assert_ne!(hash("abc"), hash("abd"));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::commons::fingerprint::Fingerprinter;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::hash::Hasher;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::Path;
+use thiserror::Error;
+
+/// Where the runner keeps the checksum of each input its answer was checked against
+pub const CHECKSUM_FILE: &str = "inputs.sum";
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("IO Error reading checksums")]
+    IoError(#[from] IoError),
+    #[error("Line {0}: expected `<checksum>  <path>`")]
+    Syntax(usize),
+}
+
+fn needs_normalising(bytes: &[u8]) -> bool {
+    bytes.contains(&b'\r')
+        || bytes
+            .split(|&b| b == b'\n')
+            .any(|line| line.last().is_some_and(u8::is_ascii_whitespace))
+        || bytes.ends_with(b"\n\n")
+}
+
+/// Converts CRLF line endings to LF, strips whitespace from the end of every line and leaves
+/// at most one newline at the end. Only copies when something needs changing.
+pub fn normalise(bytes: &[u8]) -> Cow<'_, [u8]> {
+    if !needs_normalising(bytes) {
+        return Cow::Borrowed(bytes);
+    }
+    let mut out = Vec::with_capacity(bytes.len());
+    for line in bytes.split(|&b| b == b'\n') {
+        let end = line
+            .iter()
+            .rposition(|b| !b.is_ascii_whitespace())
+            .map_or(0, |i| i + 1);
+        out.extend_from_slice(&line[..end]);
+        out.push(b'\n');
+    }
+    while out.last() == Some(&b'\n') {
+        out.pop();
+    }
+    if bytes.ends_with(b"\n") && !out.is_empty() {
+        out.push(b'\n');
+    }
+    Cow::Owned(out)
+}
+
+/// A hex digest of the normalised contents, so line ending changes alone don't alter it
+pub fn checksum(bytes: &[u8]) -> String {
+    let normalised = normalise(bytes);
+    let mut hasher = Fingerprinter::new();
+    hasher.write(&normalised);
+    // The last word is zero padded, so without the length trailing NULs wouldn't count
+    hasher.write_usize(normalised.len());
+    format!("{:032x}", hasher.finish128())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputStatus {
+    Missing,
+    /// No checksum was recorded for this input yet
+    Unrecorded(String),
+    Unchanged,
+    /// The input differs from the one the recorded checksum came from
+    Changed {
+        recorded: String,
+        current: String,
+    },
+}
+
+/// Recorded input checksums, laid out like `sha256sum` output though the digest isn't SHA-256
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    entries: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    pub fn parse(s: &str) -> Result<Self, ManifestError> {
+        let mut entries = BTreeMap::new();
+        for (i, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let (sum, path) = line.split_once("  ").ok_or(ManifestError::Syntax(i + 1))?;
+            entries.insert(path.to_string(), sum.to_string());
+        }
+        Ok(Self { entries })
+    }
+
+    /// Loads the manifest at `path`, or an empty one if there isn't one yet
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        match std::fs::read_to_string(path) {
+            Ok(s) => Self::parse(&s),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), IoError> {
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, input: &str) -> Option<&str> {
+        self.entries.get(input).map(String::as_str)
+    }
+
+    pub fn record(&mut self, input: &str, checksum: String) {
+        self.entries.insert(input.to_string(), checksum);
+    }
+
+    /// Compares `input` on disk against its recorded checksum
+    pub fn check(&self, input: &str) -> Result<InputStatus, IoError> {
+        let bytes = match std::fs::read(input) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(InputStatus::Missing),
+            Err(e) => return Err(e),
+        };
+        let current = checksum(&bytes);
+        Ok(match self.get(input) {
+            None => InputStatus::Unrecorded(current),
+            Some(recorded) if recorded == current => InputStatus::Unchanged,
+            Some(recorded) => InputStatus::Changed {
+                recorded: recorded.to_string(),
+                current,
+            },
+        })
+    }
+}
+
+impl std::fmt::Display for Manifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (path, sum) in &self.entries {
+            writeln!(f, "{}  {}", sum, path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalises_line_endings_and_whitespace() {
+        assert!(matches!(normalise(b"a\nb\n"), Cow::Borrowed(_)));
+        assert!(matches!(normalise(b""), Cow::Borrowed(_)));
+        assert_eq!(&*normalise(b"a \r\nb\t\r\n\r\n"), b"a\nb\n");
+        assert_eq!(&*normalise(b"a\r\nb"), b"a\nb");
+        assert_eq!(&*normalise(b"\n\n"), b"");
+        // Blank lines in the middle separate sections, so they stay
+        assert_eq!(&*normalise(b"a  \r\n\r\nb\r\n"), b"a\n\nb\n");
+    }
+
+    #[test]
+    fn checksums_ignore_line_endings() {
+        assert_eq!(
+            checksum(b"LLR\n\nAAA = (BBB, BBB)\n"),
+            checksum(b"LLR\r\n\r\nAAA = (BBB, BBB)\r\n")
+        );
+        assert_ne!(checksum(b"LLR\n"), checksum(b"RLL\n"));
+        assert_ne!(checksum(b"a"), checksum(b"a\0"));
+        assert_eq!(checksum(b"").len(), 32);
+    }
+
+    #[test]
+    fn manifest_round_trip() {
+        let mut manifest = Manifest::default();
+        manifest.record("inputs/02", checksum(b"2"));
+        manifest.record("inputs/01", checksum(b"1"));
+        let text = manifest.to_string();
+        assert!(text.starts_with(&format!("{}  inputs/01\n", checksum(b"1"))));
+        assert_eq!(Manifest::parse(&text).unwrap(), manifest);
+        assert!(matches!(
+            Manifest::parse("abc\n"),
+            Err(ManifestError::Syntax(1))
+        ));
+    }
+
+    #[test]
+    fn checks_inputs_on_disk() {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("01");
+        let input = input.to_str().unwrap();
+        let mut manifest = Manifest::default();
+
+        assert_eq!(manifest.check(input).unwrap(), InputStatus::Missing);
+        std::fs::write(input, "1abc2\n").unwrap();
+        let InputStatus::Unrecorded(sum) = manifest.check(input).unwrap() else {
+            panic!("expected an unrecorded input");
+        };
+        manifest.record(input, sum.clone());
+        assert_eq!(manifest.check(input).unwrap(), InputStatus::Unchanged);
+        std::fs::write(input, "1abc2\r\n").unwrap();
+        assert_eq!(manifest.check(input).unwrap(), InputStatus::Unchanged);
+        std::fs::write(input, "pqr3stu8vwx\n").unwrap();
+        assert_eq!(
+            manifest.check(input).unwrap(),
+            InputStatus::Changed {
+                recorded: sum,
+                current: checksum(b"pqr3stu8vwx\n")
+            }
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
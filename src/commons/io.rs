@@ -1,8 +1,10 @@
-use std::env;
+use crate::commons::inputs::normalise;
+use std::borrow::Cow;
 use std::error::Error as StdError;
 use std::fmt::Debug;
 use std::io::prelude::*;
 use std::io::{self, BufReader, Error as IoError};
+use std::path::Path;
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -14,6 +16,14 @@ pub enum ParseLinesError<L: StdError> {
     ParseError(L),
 }
 
+#[derive(Error, Debug)]
+pub enum InputError {
+    #[error("IO Error reading input")]
+    IoError(#[from] IoError),
+    #[error("Input isn't valid UTF-8 at byte {offset}")]
+    InvalidUtf8 { offset: usize },
+}
+
 enum InputStorage {
     Mmap(memmap2::Mmap),
     Buffer(Vec<u8>),
@@ -24,50 +34,65 @@ pub struct Input {
 }
 
 impl Input {
-    pub fn from_argv() -> Result<Self, Box<dyn std::error::Error>> {
-        match env::args().nth(1) {
-            Some(path) => Self::from_file(&path),
-            None => Self::from_stdin(),
+    /// Reads a file, normalising line endings and trailing whitespace. Files that are already
+    /// clean are used straight from the mapping, without a copy.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        let file = std::fs::File::open(path)?;
+        // Mapping zero bytes fails on some platforms, and there's nothing to map anyway
+        if file.metadata()?.len() == 0 {
+            return Ok(Self::from_bytes(Vec::new()));
         }
-    }
-
-    pub fn from_file(p: &String) -> Result<Self, Box<dyn std::error::Error>> {
-        let file = std::fs::File::open(p)?;
-        let storage = InputStorage::Mmap(unsafe { memmap2::Mmap::map(&file)? });
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let storage = match normalise(&mmap) {
+            Cow::Borrowed(_) => InputStorage::Mmap(mmap),
+            Cow::Owned(buf) => InputStorage::Buffer(buf),
+        };
         Ok(Self { storage })
     }
 
-    pub fn from_stdin() -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_stdin() -> Result<Self, InputError> {
         let stdin = io::stdin();
         let mut reader = BufReader::new(stdin.lock());
         let mut buf = Vec::with_capacity(8196);
         reader.read_to_end(&mut buf)?;
-
-        Ok(Self {
-            storage: InputStorage::Buffer(buf),
-        })
+        Ok(Self::from_bytes(buf))
     }
 
-    pub fn from_string(s: String) -> Self {
+    /// Normalises `bytes` the same way files are
+    pub fn from_bytes(mut bytes: Vec<u8>) -> Self {
+        if let Cow::Owned(normalised) = normalise(&bytes) {
+            bytes = normalised;
+        }
         Self {
-            storage: InputStorage::Buffer(s.as_bytes().to_vec()),
+            storage: InputStorage::Buffer(bytes),
         }
     }
 
-    pub fn as_str(&self) -> &str {
-        let buf = match &self.storage {
+    pub fn from_string(s: String) -> Self {
+        Self::from_bytes(s.into_bytes())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.storage {
             InputStorage::Mmap(mmap) => &mmap[..],
             InputStorage::Buffer(buf) => buf,
-        };
-        std::str::from_utf8(buf).unwrap()
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&str, InputError> {
+        std::str::from_utf8(self.as_bytes()).map_err(|e| InputError::InvalidUtf8 {
+            offset: e.valid_up_to(),
+        })
     }
 
-    pub fn as_lines_parsed<T>(&self) -> impl Iterator<Item = Result<T, <T as FromStr>::Err>> + '_
+    pub fn as_lines_parsed<T>(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<T, <T as FromStr>::Err>> + '_, InputError>
     where
         T: FromStr,
         <T as FromStr>::Err: StdError,
     {
-        self.as_str().lines().map(|l| l.parse::<T>())
+        Ok(self.as_str()?.lines().map(|l| l.parse::<T>()))
     }
 }
 
@@ -78,7 +103,60 @@ mod tests {
     #[test]
     fn items() {
         let input = Input::from_string("1\n2\n3\n4".to_string());
-        let output: Vec<u32> = input.as_lines_parsed().map(|x| x.unwrap()).collect();
+        let output: Vec<u32> = input
+            .as_lines_parsed()
+            .unwrap()
+            .map(|x| x.unwrap())
+            .collect();
         assert_eq!(vec![1, 2, 3, 4], output);
     }
+
+    #[test]
+    fn files_are_normalised() {
+        let path = std::env::temp_dir().join(format!("aoc-io-{}", std::process::id()));
+        std::fs::write(&path, "AAA = (BBB, CCC)  \r\nBBB = (DDD, EEE)\r\n").unwrap();
+        let input = Input::from_file(&path).unwrap();
+        let line = input.as_str().unwrap().lines().next().unwrap();
+        assert_eq!(&line[7..10], "BBB");
+        assert_eq!(
+            input.as_str().unwrap(),
+            "AAA = (BBB, CCC)\nBBB = (DDD, EEE)\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn strings_are_normalised() {
+        let input = Input::from_string("1 \r\n2\r\n\r\n".to_string());
+        assert_eq!(input.as_str().unwrap(), "1\n2\n");
+    }
+
+    #[test]
+    fn empty_files() {
+        let path = std::env::temp_dir().join(format!("aoc-io-empty-{}", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let input = Input::from_file(&path).unwrap();
+        assert_eq!(input.as_bytes(), b"");
+        assert_eq!(input.as_str().unwrap(), "");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let input = Input::from_bytes(b"12\n3\xff4\n".to_vec());
+        assert_eq!(input.as_bytes(), b"12\n3\xff4\n");
+        assert!(matches!(
+            input.as_str(),
+            Err(InputError::InvalidUtf8 { offset: 4 })
+        ));
+        assert!(input.as_lines_parsed::<u32>().is_err());
+    }
+
+    #[test]
+    fn missing_files() {
+        assert!(matches!(
+            Input::from_file("/nonexistent/aoc-input"),
+            Err(InputError::IoError(_))
+        ));
+    }
 }
@@ -13,6 +13,7 @@ pub fn gcd<I>(mut a: I, mut b: I) -> I
 where
     I: Div<Output = I> + Rem<Output = I> + Sub<Output = I> + PartialEq<I> + Copy,
 {
+    #[allow(clippy::eq_op)]
     let zero = b - b;
     while b != zero {
         let new_a = b;
@@ -0,0 +1,240 @@
+use crate::commons::hash::{BuildFxHasher, FastMap};
+use std::hash::Hash;
+
+/// What `Memo` and `DenseMemo` have in common, for recursive functions that work with either
+pub trait MemoTable<K, V> {
+    /// Returns the value for `key`, calling `f` to work it out the first time
+    fn get_or_compute<F>(&mut self, key: K, f: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V;
+}
+
+/// A memo table for recursive functions. The function being memoised takes the table as an
+/// argument and passes it down to its recursive calls through `get_or_compute`.
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    table: FastMap<K, V>,
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            table: FastMap::with_capacity_and_hasher(capacity, BuildFxHasher::default()),
+        }
+    }
+
+    /// Returns the value for `key`, calling `f` to work it out the first time
+    pub fn get_or_compute<F>(&mut self, key: K, f: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(v) = self.table.get(&key) {
+            return v.clone();
+        }
+        let v = f(self);
+        self.table.insert(key, v.clone());
+        v
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.table.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Forgets every entry but keeps the allocation, for reuse between independent problems
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+}
+
+impl<K, V> MemoTable<K, V> for Memo<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    fn get_or_compute<F>(&mut self, key: K, f: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        Memo::get_or_compute(self, key, f)
+    }
+}
+
+impl<K, V> Default for Memo<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A memo table backed by a flat array, for keys that are `N` integers each below a known bound
+#[derive(Clone, Debug)]
+pub struct DenseMemo<V, const N: usize> {
+    dims: [usize; N],
+    values: Vec<Option<V>>,
+}
+
+impl<V, const N: usize> DenseMemo<V, N>
+where
+    V: Clone,
+{
+    /// A table for keys where `key[i] < dims[i]`
+    pub fn new(dims: [usize; N]) -> Self {
+        let len = dims.iter().product();
+        let mut values = Vec::with_capacity(len);
+        values.resize(len, None);
+        Self { dims, values }
+    }
+
+    fn index(&self, key: &[usize; N]) -> usize {
+        let mut i = 0;
+        for (k, dim) in key.iter().zip(self.dims) {
+            assert!(*k < dim, "Key {:?} out of range for {:?}", key, self.dims);
+            i = i * dim + k;
+        }
+        i
+    }
+
+    /// Returns the value for `key`, calling `f` to work it out the first time
+    pub fn get_or_compute<F>(&mut self, key: [usize; N], f: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        let i = self.index(&key);
+        if let Some(v) = &self.values[i] {
+            return v.clone();
+        }
+        let v = f(self);
+        self.values[i] = Some(v.clone());
+        v
+    }
+
+    pub fn get(&self, key: &[usize; N]) -> Option<&V> {
+        self.values[self.index(key)].as_ref()
+    }
+
+    /// Forgets every entry but keeps the allocation, for reuse between independent problems
+    pub fn clear(&mut self) {
+        self.values.fill(None);
+    }
+
+    /// Forgets every entry and changes the key bounds, reusing the allocation where possible
+    pub fn reset(&mut self, dims: [usize; N]) {
+        self.dims = dims;
+        self.values.clear();
+        self.values.resize(dims.iter().product(), None);
+    }
+}
+
+impl<V, const N: usize> MemoTable<[usize; N], V> for DenseMemo<V, N>
+where
+    V: Clone,
+{
+    fn get_or_compute<F>(&mut self, key: [usize; N], f: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        DenseMemo::get_or_compute(self, key, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: [(&str, &[usize], u64); 6] = [
+        ("???.###", &[1, 1, 3], 1),
+        (".??..??...?##.", &[1, 1, 3], 4),
+        ("?#?#?#?#?#?#?#?", &[1, 3, 1, 6], 1),
+        ("????.#...#...", &[4, 1, 1], 1),
+        ("????.######..#####.", &[1, 6, 5], 4),
+        ("?###????????", &[3, 2, 1], 10),
+    ];
+
+    /// Spring arrangements: how many ways `springs` can be filled in to give `groups`
+    fn arrangements<M>(memo: &mut M, springs: &[u8], groups: &[usize]) -> u64
+    where
+        M: MemoTable<[usize; 2], u64>,
+    {
+        memo.get_or_compute([springs.len(), groups.len()], |memo| {
+            let Some((&first, rest)) = springs.split_first() else {
+                return groups.is_empty() as u64;
+            };
+            let mut count = 0;
+            if first != b'#' {
+                count += arrangements(memo, rest, groups);
+            }
+            if first != b'.' {
+                if let Some((&g, groups_rest)) = groups.split_first() {
+                    let fits = springs.len() >= g
+                        && !springs[..g].contains(&b'.')
+                        && springs.get(g) != Some(&b'#');
+                    if fits {
+                        let next = springs.get(g + 1..).unwrap_or(&[]);
+                        count += arrangements(memo, next, groups_rest);
+                    }
+                }
+            }
+            count
+        })
+    }
+
+    #[test]
+    fn memo_spring_arrangements() {
+        let mut memo = Memo::with_capacity(64);
+        for (springs, groups, expected) in EXAMPLES {
+            memo.clear();
+            assert_eq!(
+                arrangements(&mut memo, springs.as_bytes(), groups),
+                expected
+            );
+            assert!(!memo.is_empty());
+        }
+
+        // Unfolded five times, as in part two
+        let springs = ["?###????????"; 5].join("?");
+        let groups = [3, 2, 1].repeat(5);
+        memo.clear();
+        assert_eq!(arrangements(&mut memo, springs.as_bytes(), &groups), 506250);
+    }
+
+    #[test]
+    fn dense_memo_spring_arrangements() {
+        let mut memo = DenseMemo::new([0, 0]);
+        for (springs, groups, expected) in EXAMPLES {
+            memo.reset([springs.len() + 1, groups.len() + 1]);
+            assert_eq!(
+                arrangements(&mut memo, springs.as_bytes(), groups),
+                expected
+            );
+        }
+        assert_eq!(memo.get(&[0, 0]), Some(&1));
+        memo.clear();
+        assert_eq!(memo.get(&[0, 0]), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dense_memo_checks_bounds() {
+        let mut memo = DenseMemo::<u8, 2>::new([2, 3]);
+        memo.get_or_compute([1, 3], |_| 0);
+    }
+}
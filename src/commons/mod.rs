@@ -1,4 +1,19 @@
+pub mod aocbench;
+pub mod bitset;
+pub mod cards;
+pub mod cli;
+pub mod cycle;
+pub mod dp;
+pub mod fingerprint;
 pub mod geom;
 pub mod grid;
+pub mod hash;
+pub mod inputs;
 pub mod io;
+pub mod json;
 pub mod math;
+pub mod memo;
+pub mod parallel;
+pub mod piecewise;
+pub mod report;
+pub mod serial;
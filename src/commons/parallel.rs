@@ -0,0 +1,157 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+static FORCE_SEQUENTIAL: AtomicBool = AtomicBool::new(false);
+
+/// Environment variable that forces the helpers here to run on the calling thread, so benchmark
+/// numbers aren't skewed by thread startup or other days competing for cores
+pub const SEQUENTIAL_ENV: &str = "AOC_SEQUENTIAL";
+
+pub fn set_sequential(sequential: bool) {
+    FORCE_SEQUENTIAL.store(sequential, Ordering::Relaxed);
+}
+
+pub fn is_sequential() -> bool {
+    !cfg!(feature = "parallel")
+        || FORCE_SEQUENTIAL.load(Ordering::Relaxed)
+        || std::env::var(SEQUENTIAL_ENV).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+pub fn available_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+fn threads_for(len: usize) -> usize {
+    if is_sequential() {
+        1
+    } else {
+        available_threads().min(len)
+    }
+}
+
+/// Splits `items` into fixed chunks and maps each with `f` on up to `threads` workers, which
+/// take the next chunk as they finish so uneven work still balances. Chunk results come back in
+/// order.
+fn map_chunks<T, U, F>(items: &[T], threads: usize, f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&[T]) -> U + Sync,
+{
+    let threads = threads.min(items.len()).max(1);
+    let chunk_size = if threads == 1 {
+        items.len().max(1)
+    } else {
+        (items.len() / (threads * 4)).max(1)
+    };
+    if threads == 1 {
+        return items.chunks(chunk_size).map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut done = thread::scope(|s| {
+        let workers = (0..threads)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let start = next.fetch_add(chunk_size, Ordering::Relaxed);
+                        if start >= items.len() {
+                            break;
+                        }
+                        let end = (start + chunk_size).min(items.len());
+                        done.push((start, f(&items[start..end])));
+                    }
+                    done
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    done.sort_unstable_by_key(|(start, _)| *start);
+    done.into_iter().map(|(_, u)| u).collect()
+}
+
+/// Maps `f` over `items` on exactly `threads` workers, whatever the `parallel` feature says
+pub fn map_with_threads<T, U, F>(items: &[T], threads: usize, f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync,
+{
+    map_chunks(items, threads, |chunk| {
+        chunk.iter().map(&f).collect::<Vec<_>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Maps `f` over `items`, in parallel when built with the `parallel` feature and not forced
+/// sequential. Results are in the same order as `items`.
+pub fn map<T, U, F>(items: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync,
+{
+    map_with_threads(items, threads_for(items.len()), f)
+}
+
+/// Maps then folds `items`. Chunks are reduced left to right and then combined in order, so the
+/// result is the same whatever the thread count as long as `reduce` is associative.
+pub fn map_reduce<T, U, I, M, R>(items: &[T], identity: I, map: M, reduce: R) -> U
+where
+    T: Sync,
+    U: Send,
+    I: Fn() -> U + Sync,
+    M: Fn(&T) -> U + Sync,
+    R: Fn(U, U) -> U + Sync,
+{
+    map_chunks(items, threads_for(items.len()), |chunk| {
+        chunk
+            .iter()
+            .fold(identity(), |acc, item| reduce(acc, map(item)))
+    })
+    .into_iter()
+    .fold(identity(), &reduce)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_keeps_order() {
+        let items = (0..1000u64).collect::<Vec<_>>();
+        let expected = items.iter().map(|i| i * i).collect::<Vec<_>>();
+
+        assert_eq!(map(&items, |i| i * i), expected);
+        for threads in [1, 2, 3, 8, 2000] {
+            assert_eq!(map_with_threads(&items, threads, |i| i * i), expected);
+        }
+        assert!(map_with_threads(&Vec::<u8>::new(), 4, |i| *i).is_empty());
+    }
+
+    #[test]
+    fn map_reduce_is_deterministic() {
+        let items = (0..10_000u64).collect::<Vec<_>>();
+        let sum = map_reduce(&items, || 0, |i| i * 2, |a, b| a + b);
+        assert_eq!(sum, 99_990_000);
+
+        // Not commutative, so this only holds if chunks are combined in order
+        let concat = map_reduce(&items[..50], String::new, |i| i.to_string(), |a, b| a + &b);
+        let expected = items[..50]
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<String>();
+        assert_eq!(concat, expected);
+        assert_eq!(
+            map_reduce(&Vec::<u64>::new(), || 7, |i| *i, |a, b| a + b),
+            7
+        );
+    }
+}
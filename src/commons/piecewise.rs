@@ -0,0 +1,605 @@
+use crate::commons::hash::FastMap;
+use std::collections::VecDeque;
+use std::ops::Range;
+use thiserror::Error;
+
+/// Moves the values in `src` so that `src.start` lands on `dst_start`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    pub src: Range<u64>,
+    pub dst_start: u64,
+}
+
+impl Mapping {
+    pub fn new(dst_start: u64, src_start: u64, length: u64) -> Option<Self> {
+        src_start.checked_add(length)?;
+        dst_start.checked_add(length)?;
+        Some(Self {
+            src: src_start..src_start + length,
+            dst_start,
+        })
+    }
+
+    fn map(&self, v: u64) -> u64 {
+        v - self.src.start + self.dst_start
+    }
+
+    fn dst(&self) -> Range<u64> {
+        self.dst_start..self.dst_start + (self.src.end - self.src.start)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum StageError {
+    #[error("Source ranges {0:?} and {1:?} overlap")]
+    OverlappingSources(Range<u64>, Range<u64>),
+    #[error("Destination ranges {0:?} and {1:?} overlap")]
+    OverlappingDestinations(Range<u64>, Range<u64>),
+    #[error("Mappings don't cover the same values they map to, so some values have no inverse")]
+    NotBijective,
+}
+
+/// Pairs of ranges in `ranges`, sorted by start, that overlap each other
+fn overlapping(ranges: &[Range<u64>]) -> Vec<(Range<u64>, Range<u64>)> {
+    let mut overlaps = Vec::new();
+    for (i, a) in ranges.iter().enumerate() {
+        for b in ranges[i + 1..].iter().take_while(|b| b.start < a.end) {
+            overlaps.push((a.clone(), b.clone()));
+        }
+    }
+    overlaps
+}
+
+/// A piecewise map made of `Mapping`s, leaving values outside all of them unchanged
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stage {
+    mappings: Vec<Mapping>,
+}
+
+impl Stage {
+    /// Fails if any of the mappings' sources overlap, as the values they share would be ambiguous
+    pub fn new(mut mappings: Vec<Mapping>) -> Result<Self, StageError> {
+        mappings.retain(|m| !m.src.is_empty());
+        mappings.sort_unstable_by_key(|m| m.src.start);
+        let sources = mappings.iter().map(|m| m.src.clone()).collect::<Vec<_>>();
+        if let Some((a, b)) = overlapping(&sources).into_iter().next() {
+            return Err(StageError::OverlappingSources(a, b));
+        }
+        Ok(Self { mappings })
+    }
+
+    /// Mappings sorted by source
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    pub fn apply(&self, v: u64) -> u64 {
+        let i = self.mappings.partition_point(|m| m.src.end <= v);
+        match self.mappings.get(i) {
+            Some(m) if m.src.contains(&v) => m.map(v),
+            _ => v,
+        }
+    }
+
+    /// Splits `range` where mappings start and end, calling `f` with each piece and where its
+    /// first value maps to
+    fn split<F: FnMut(Range<u64>, u64)>(&self, range: Range<u64>, mut f: F) {
+        let mut start = range.start;
+        let first = self.mappings.partition_point(|m| m.src.end <= start);
+        for m in &self.mappings[first..] {
+            if m.src.start >= range.end {
+                break;
+            }
+            if m.src.start > start {
+                f(start..m.src.start, start);
+            }
+            let from = start.max(m.src.start);
+            let to = range.end.min(m.src.end);
+            f(from..to, m.map(from));
+            start = to;
+        }
+        if start < range.end {
+            f(start..range.end, start);
+        }
+    }
+
+    /// Maps every value in `range`, which may split it into several pieces
+    pub fn apply_range(&self, range: Range<u64>, out: &mut Vec<Range<u64>>) {
+        self.split(range, |piece, to| {
+            out.push(to..to + (piece.end - piece.start))
+        });
+    }
+
+    /// Finds every value that maps into `range`, the reverse of `apply_range`
+    pub fn preimage_range(&self, range: Range<u64>, out: &mut Vec<Range<u64>>) {
+        // Values no mapping moves
+        let mut start = range.start;
+        let first = self.mappings.partition_point(|m| m.src.end <= start);
+        for m in &self.mappings[first..] {
+            if m.src.start >= range.end {
+                break;
+            }
+            if m.src.start > start {
+                out.push(start..m.src.start);
+            }
+            start = start.max(m.src.end);
+        }
+        if start < range.end {
+            out.push(start..range.end);
+        }
+
+        // Values a mapping moves into the range
+        for m in &self.mappings {
+            let dst_end = m.dst_start + (m.src.end - m.src.start);
+            let from = range.start.max(m.dst_start);
+            let to = range.end.min(dst_end);
+            if from < to {
+                let src_from = from - m.dst_start + m.src.start;
+                out.push(src_from..src_from + (to - from));
+            }
+        }
+    }
+
+    /// Values between the lowest and highest mapped source that no mapping covers
+    pub fn gaps(&self) -> Vec<Range<u64>> {
+        let covered = merge_ranges(self.mappings.iter().map(|m| m.src.clone()).collect());
+        covered.windows(2).map(|w| w[0].end..w[1].start).collect()
+    }
+
+    /// The stage that undoes this one. Only stages that map the values they cover onto exactly
+    /// those values, without overlapping destinations, can be undone.
+    pub fn inverse(&self) -> Result<Stage, StageError> {
+        let mut destinations = self.mappings.iter().map(Mapping::dst).collect::<Vec<_>>();
+        destinations.sort_unstable_by_key(|r| r.start);
+        if let Some((a, b)) = overlapping(&destinations).into_iter().next() {
+            return Err(StageError::OverlappingDestinations(a, b));
+        }
+        let sources = self.mappings.iter().map(|m| m.src.clone()).collect();
+        if merge_ranges(sources) != merge_ranges(destinations) {
+            return Err(StageError::NotBijective);
+        }
+
+        Stage::new(
+            self.mappings
+                .iter()
+                .map(|m| Mapping {
+                    src: m.dst(),
+                    dst_start: m.src.start,
+                })
+                .collect(),
+        )
+    }
+
+    /// A single stage doing the same as applying this one and then `then`
+    pub fn compose(&self, then: &Stage) -> Stage {
+        let mut mappings: Vec<Mapping> = Vec::new();
+        self.split(0..u64::MAX, |piece, mid| {
+            let mid_range = mid..mid + (piece.end - piece.start);
+            then.split(mid_range, |part, to| {
+                let from = piece.start + (part.start - mid);
+                if from == to {
+                    return;
+                }
+                let len = part.end - part.start;
+                // Pieces come in order, so join any that continue the previous one
+                match mappings.last_mut() {
+                    Some(last) if last.src.end == from && last.dst().end == to => {
+                        last.src.end += len;
+                    }
+                    _ => mappings.push(Mapping {
+                        src: from..from + len,
+                        dst_start: to,
+                    }),
+                }
+            });
+        });
+        Stage { mappings }
+    }
+}
+
+/// Sorts `ranges` and joins any that overlap or touch
+pub fn merge_ranges(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.retain(|r| !r.is_empty());
+    ranges.sort_unstable_by_key(|r| r.start);
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for r in ranges {
+        match merged.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => merged.push(r),
+        }
+    }
+    merged
+}
+
+/// Stages applied one after another
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    pub fn new(stages: Vec<Stage>) -> Self {
+        Self { stages }
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    pub fn apply(&self, v: u64) -> u64 {
+        self.stages.iter().fold(v, |v, stage| stage.apply(v))
+    }
+
+    /// Composes every stage into one
+    pub fn flatten(&self) -> Stage {
+        self.stages
+            .iter()
+            .fold(Stage::default(), |acc, stage| acc.compose(stage))
+    }
+
+    /// Maps whole ranges through every stage, returning the sorted, merged ranges they end up in
+    pub fn apply_ranges(&self, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        let mut ranges = merge_ranges(ranges);
+        let mut next = Vec::with_capacity(ranges.len());
+        for stage in &self.stages {
+            for r in ranges.drain(..) {
+                stage.apply_range(r, &mut next);
+            }
+            ranges = merge_ranges(std::mem::take(&mut next));
+        }
+        ranges
+    }
+
+    /// Finds every value that ends up in `ranges`, returning them sorted and merged
+    pub fn preimage_ranges(&self, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        let mut ranges = merge_ranges(ranges);
+        let mut next = Vec::with_capacity(ranges.len());
+        for stage in self.stages.iter().rev() {
+            for r in ranges.drain(..) {
+                stage.preimage_range(r, &mut next);
+            }
+            ranges = merge_ranges(std::mem::take(&mut next));
+        }
+        ranges
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RouteError {
+    #[error("Unknown category {0:?}")]
+    UnknownCategory(String),
+    #[error("No chain of maps converts {from:?} to {to:?}")]
+    NotConnected { from: String, to: String },
+}
+
+/// Named categories with stages converting between some pairs of them. Conversions can be
+/// chained, and followed backwards to find what maps to a value.
+#[derive(Clone, Debug, Default)]
+pub struct ConversionGraph {
+    categories: FastMap<String, usize>,
+    conversions: Vec<(usize, usize, Stage)>,
+}
+
+impl ConversionGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn category(&mut self, name: &str) -> usize {
+        let next = self.categories.len();
+        *self.categories.entry(name.to_string()).or_insert(next)
+    }
+
+    /// Adds a stage converting values in category `from` to category `to`
+    pub fn add(&mut self, from: &str, to: &str, stage: Stage) {
+        let from = self.category(from);
+        let to = self.category(to);
+        self.conversions.push((from, to, stage));
+    }
+
+    fn lookup(&self, name: &str) -> Result<usize, RouteError> {
+        self.categories
+            .get(name)
+            .copied()
+            .ok_or_else(|| RouteError::UnknownCategory(name.to_string()))
+    }
+
+    /// The shortest chain of conversions from `from` to `to`, following maps either way
+    pub fn route(&self, from: &str, to: &str) -> Result<Route<'_>, RouteError> {
+        let start = self.lookup(from)?;
+        let end = self.lookup(to)?;
+
+        // Breadth first, remembering the step that first reached each category
+        let mut reached_by = vec![None; self.categories.len()];
+        let mut queue = VecDeque::from([start]);
+        while let Some(at) = queue.pop_front() {
+            if at == end {
+                break;
+            }
+            for (i, (src, dst, _)) in self.conversions.iter().enumerate() {
+                let (next, forward) = match at {
+                    _ if *src == at => (*dst, true),
+                    _ if *dst == at => (*src, false),
+                    _ => continue,
+                };
+                if next != start && reached_by[next].is_none() {
+                    reached_by[next] = Some((i, forward));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut steps = Vec::new();
+        let mut at = end;
+        while at != start {
+            let (i, forward) = reached_by[at].ok_or_else(|| RouteError::NotConnected {
+                from: from.to_string(),
+                to: to.to_string(),
+            })?;
+            steps.push((i, forward));
+            let (src, dst, _) = &self.conversions[i];
+            at = if forward { *src } else { *dst };
+        }
+        steps.reverse();
+        Ok(Route { graph: self, steps })
+    }
+}
+
+/// A chain of conversions found by `ConversionGraph::route`
+#[derive(Clone, Debug)]
+pub struct Route<'a> {
+    graph: &'a ConversionGraph,
+    steps: Vec<(usize, bool)>,
+}
+
+impl Route<'_> {
+    /// Converts a single value. Following maps backwards, that can give many values.
+    pub fn map(&self, v: u64) -> Vec<Range<u64>> {
+        let single = v..v + 1;
+        self.map_ranges(vec![single])
+    }
+
+    /// Converts every value in `ranges`, returning the sorted, merged ranges they end up in
+    pub fn map_ranges(&self, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        let mut ranges = merge_ranges(ranges);
+        let mut next = Vec::with_capacity(ranges.len());
+        for &(i, forward) in &self.steps {
+            let stage = &self.graph.conversions[i].2;
+            for r in ranges.drain(..) {
+                if forward {
+                    stage.apply_range(r, &mut next);
+                } else {
+                    stage.preimage_range(r, &mut next);
+                }
+            }
+            ranges = merge_ranges(std::mem::take(&mut next));
+        }
+        ranges
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    fn mappings(mappings: &[(u64, u64, u64)]) -> Vec<Mapping> {
+        mappings
+            .iter()
+            .map(|&(dst, src, len)| Mapping::new(dst, src, len).unwrap())
+            .collect()
+    }
+
+    fn stage(m: &[(u64, u64, u64)]) -> Stage {
+        Stage::new(mappings(m)).unwrap()
+    }
+
+    #[test]
+    fn stage_apply() {
+        // Day 5 example seed-to-soil map
+        let s = stage(&[(50, 98, 2), (52, 50, 48)]);
+        assert_eq!(
+            [79, 14, 55, 13, 0, 49, 50, 97, 98, 99, 100].map(|v| s.apply(v)),
+            [81, 14, 57, 13, 0, 49, 52, 99, 50, 51, 100]
+        );
+        assert!(Mapping::new(0, u64::MAX, 2).is_none());
+    }
+
+    #[test]
+    fn ranges_split_at_mapping_edges() {
+        let s = stage(&[(50, 98, 2), (52, 50, 48)]);
+        let mut out = Vec::new();
+        s.apply_range(40..105, &mut out);
+        assert_eq!(out, [40..50, 52..100, 50..52, 100..105]);
+        assert_eq!(merge_ranges(out), [40..105]);
+    }
+
+    fn example_pipeline() -> Pipeline {
+        Pipeline::new(vec![
+            stage(&[(50, 98, 2), (52, 50, 48)]),
+            stage(&[(0, 15, 37), (37, 52, 2), (39, 0, 15)]),
+            stage(&[(88, 18, 7), (18, 25, 70)]),
+        ])
+    }
+
+    #[test]
+    fn pipeline_ranges_match_pointwise() {
+        let p = example_pipeline();
+        let seeds = vec![79..93, 55..68];
+        let ranges = p.apply_ranges(seeds.clone());
+        let mut expected = seeds
+            .into_iter()
+            .flatten()
+            .map(|v| p.apply(v))
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(ranges.into_iter().flatten().collect::<Vec<_>>(), expected);
+
+        let back = p.preimage_ranges(vec![77..78]);
+        assert!(back.iter().any(|r| r.contains(&82)));
+        assert!(back
+            .iter()
+            .flat_map(|r| r.clone())
+            .all(|v| p.apply(v) == 77));
+    }
+
+    /// Xorshift, so the property tests below are repeatable without pulling in a crate
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % n
+        }
+    }
+
+    /// Cuts `0..size` into blocks at random and shuffles them, giving an invertible stage
+    fn random_bijection(rng: &mut Rng, size: u64) -> Stage {
+        let mut cuts = (0..rng.below(6))
+            .map(|_| rng.below(size))
+            .chain([0, size])
+            .collect::<Vec<_>>();
+        cuts.sort_unstable();
+        cuts.dedup();
+        let mut blocks = cuts.windows(2).map(|w| w[0]..w[1]).collect::<Vec<_>>();
+        for i in (1..blocks.len()).rev() {
+            blocks.swap(i, rng.below(i as u64 + 1) as usize);
+        }
+        let mut dst_start = 0;
+        let mut mappings = Vec::new();
+        for block in blocks {
+            mappings.push(Mapping::new(dst_start, block.start, block.end - block.start).unwrap());
+            dst_start += block.end - block.start;
+        }
+        Stage::new(mappings).unwrap()
+    }
+
+    #[test]
+    fn compose_matches_applying_in_turn() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..200 {
+            let a = random_bijection(&mut rng, 60);
+            let b = random_bijection(&mut rng, 40);
+            let ab = a.compose(&b);
+            for v in 0..80 {
+                assert_eq!(ab.apply(v), b.apply(a.apply(v)), "{:?} then {:?}", a, b);
+            }
+            assert_eq!(Stage::new(ab.mappings().to_vec()).as_ref(), Ok(&ab));
+        }
+    }
+
+    #[test]
+    fn inverse_of_composition() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..200 {
+            let a = random_bijection(&mut rng, 50);
+            let b = random_bijection(&mut rng, 70);
+            let ab = a.compose(&b);
+            let inverse = ab.inverse().unwrap();
+            let reversed = b.inverse().unwrap().compose(&a.inverse().unwrap());
+            for v in 0..100 {
+                assert_eq!(inverse.apply(ab.apply(v)), v);
+                assert_eq!(ab.apply(inverse.apply(v)), v);
+                assert_eq!(inverse.apply(v), reversed.apply(v));
+            }
+        }
+    }
+
+    #[test]
+    fn pipeline_flattens() {
+        let p = example_pipeline();
+        let flat = p.flatten();
+        assert!((0..120).all(|v| flat.apply(v) == p.apply(v)));
+        assert_eq!(Pipeline::default().flatten(), Stage::default());
+    }
+
+    #[test]
+    fn overlaps_and_gaps() {
+        assert_eq!(
+            Stage::new(mappings(&[
+                (0, 40, 5),
+                (100, 12, 10),
+                (50, 30, 5),
+                (0, 10, 5)
+            ])),
+            Err(StageError::OverlappingSources(10..15, 12..22))
+        );
+        // Touching sources don't overlap, and empty ones are dropped
+        assert!(Stage::new(mappings(&[(0, 10, 5), (100, 15, 10), (7, 12, 0)])).is_ok());
+
+        let s = stage(&[(0, 10, 5), (100, 15, 7), (50, 30, 5), (60, 40, 5)]);
+        assert_eq!(s.gaps(), [22..30, 35..40]);
+
+        // Two sources landing on the same values
+        let s = stage(&[(0, 10, 5), (2, 20, 5)]);
+        assert_eq!(
+            s.inverse(),
+            Err(StageError::OverlappingDestinations(0..5, 2..7))
+        );
+
+        // Values moved out of 10..15 leave nothing mapping back into it
+        let s = stage(&[(0, 10, 5)]);
+        assert_eq!(s.inverse(), Err(StageError::NotBijective));
+        let swap = stage(&[(0, 10, 5), (10, 0, 5)]);
+        assert_eq!(swap.inverse().unwrap().mappings(), swap.mappings());
+    }
+
+    fn example_graph() -> ConversionGraph {
+        let mut g = ConversionGraph::new();
+        g.add("seed", "soil", stage(&[(50, 98, 2), (52, 50, 48)]));
+        g.add(
+            "soil",
+            "fertilizer",
+            stage(&[(0, 15, 37), (37, 52, 2), (39, 0, 15)]),
+        );
+        g.add(
+            "fertilizer",
+            "water",
+            stage(&[(49, 53, 8), (0, 11, 42), (42, 0, 7), (57, 7, 4)]),
+        );
+        g.add("water", "light", stage(&[(88, 18, 7), (18, 25, 70)]));
+        g.add("sky", "space", Stage::default());
+        g
+    }
+
+    #[test]
+    fn graph_routes_either_way() {
+        let g = example_graph();
+        let seed_to_light = g.route("seed", "light").unwrap();
+        assert_eq!(seed_to_light.map_ranges(vec![79..80]), [74..75]);
+        assert_eq!(
+            g.route("seed", "seed").unwrap().map_ranges(vec![5..6]),
+            [5..6]
+        );
+
+        let light_to_seed = g.route("light", "seed").unwrap();
+        assert!(light_to_seed
+            .map_ranges(vec![74..75])
+            .iter()
+            .any(|r| r.contains(&79)));
+
+        // Partway along and back again
+        let water_to_soil = g.route("water", "soil").unwrap();
+        let soil = water_to_soil.map_ranges(vec![0..100]);
+        assert_eq!(g.route("soil", "water").unwrap().map_ranges(soil), [0..100]);
+    }
+
+    #[test]
+    fn graph_reports_bad_routes() {
+        let g = example_graph();
+        assert_eq!(
+            g.route("seed", "humidity").unwrap_err(),
+            RouteError::UnknownCategory("humidity".to_string())
+        );
+        assert_eq!(
+            g.route("space", "seed").unwrap_err(),
+            RouteError::NotConnected {
+                from: "space".to_string(),
+                to: "seed".to_string()
+            }
+        );
+    }
+}
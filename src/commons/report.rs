@@ -0,0 +1,163 @@
+use crate::commons::json::Json;
+
+/// How a day or one of its parts turned out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Correct,
+    Wrong,
+    /// There was no answer to check against
+    Unchecked,
+    Failed,
+    NoInput,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Correct => "correct",
+            Status::Wrong => "wrong",
+            Status::Unchecked => "unchecked",
+            Status::Failed => "failed",
+            Status::NoInput => "no input",
+        }
+    }
+
+    /// Compares an answer with the expected one, if there is one
+    pub fn check(answer: &str, expected: Option<&str>) -> Self {
+        match expected {
+            Some(expected) if expected.trim() == answer.trim() => Status::Correct,
+            Some(_) => Status::Wrong,
+            None => Status::Unchecked,
+        }
+    }
+
+    /// Wrong if any part is, correct if all of them are and unchecked otherwise
+    pub fn combine(statuses: impl IntoIterator<Item = Status>) -> Self {
+        let mut combined = Status::Correct;
+        for status in statuses {
+            match status {
+                Status::Correct => {}
+                Status::Unchecked => combined = Status::Unchecked,
+                other => return other,
+            }
+        }
+        combined
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartReport {
+    pub part: u8,
+    pub answer: String,
+    pub expected: Option<String>,
+    /// `None` when the time taken isn't known for the part on its own
+    pub nanos: Option<u128>,
+}
+
+impl PartReport {
+    pub fn status(&self) -> Status {
+        Status::check(&self.answer, self.expected.as_deref())
+    }
+}
+
+/// The result of running a day, as reported by both the days themselves and the runner
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DayReport {
+    pub day: u32,
+    pub status: Status,
+    pub parts: Vec<PartReport>,
+    pub error: Option<String>,
+    pub nanos: u128,
+    /// The input path, `-` being standard input
+    pub input: String,
+    /// One of `missing`, `unrecorded`, `unchanged` or `changed`, when checked against
+    /// `inputs.sum`
+    pub input_status: Option<&'static str>,
+    pub checksum: Option<String>,
+}
+
+impl DayReport {
+    pub fn to_json(&self) -> Json {
+        let parts = self
+            .parts
+            .iter()
+            .map(|part| {
+                Json::object([
+                    ("part", part.part.into()),
+                    ("answer", part.answer.as_str().into()),
+                    ("expected", part.expected.clone().into()),
+                    ("nanos", part.nanos.into()),
+                    ("status", part.status().as_str().into()),
+                ])
+            })
+            .collect::<Vec<_>>();
+        Json::object([
+            ("day", self.day.into()),
+            ("status", self.status.as_str().into()),
+            ("parts", parts.into()),
+            ("error", self.error.clone().into()),
+            ("nanos", self.nanos.into()),
+            ("input", self.input.as_str().into()),
+            ("input_status", self.input_status.into()),
+            ("checksum", self.checksum.clone().into()),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses() {
+        assert_eq!(Status::check("142", Some("142\n")), Status::Correct);
+        assert_eq!(Status::check("142", Some("281")), Status::Wrong);
+        assert_eq!(Status::check("142", None), Status::Unchecked);
+        assert_eq!(Status::combine([]), Status::Correct);
+        assert_eq!(
+            Status::combine([Status::Correct, Status::Unchecked]),
+            Status::Unchecked
+        );
+        assert_eq!(
+            Status::combine([Status::Unchecked, Status::Wrong, Status::Correct]),
+            Status::Wrong
+        );
+    }
+
+    #[test]
+    fn writes_json() {
+        let report = DayReport {
+            day: 1,
+            status: Status::Wrong,
+            parts: vec![
+                PartReport {
+                    part: 1,
+                    answer: "142".to_string(),
+                    expected: Some("142".to_string()),
+                    nanos: Some(10),
+                },
+                PartReport {
+                    part: 2,
+                    answer: "281".to_string(),
+                    expected: Some("280".to_string()),
+                    nanos: None,
+                },
+            ],
+            error: None,
+            nanos: 50,
+            input: "inputs/01".to_string(),
+            input_status: Some("unchanged"),
+            checksum: Some("abc".to_string()),
+        };
+        assert_eq!(
+            report.to_json().to_string(),
+            concat!(
+                r#"{"day":1,"status":"wrong","parts":["#,
+                r#"{"part":1,"answer":"142","expected":"142","nanos":10,"status":"correct"},"#,
+                r#"{"part":2,"answer":"281","expected":"280","nanos":null,"status":"wrong"}"#,
+                r#"],"error":null,"nanos":50,"input":"inputs/01","input_status":"unchanged","#,
+                r#""checksum":"abc"}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,588 @@
+use crate::commons::geom::Point;
+use crate::commons::grid::{BitGrid, Grid, SingleVecGrid, SparseGrid};
+use bitvec::prelude::*;
+use std::fmt::Display;
+use std::io::Error as IoError;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+const MAGIC: &[u8; 4] = b"AOC\x01";
+
+#[derive(Error, Debug)]
+pub enum SerialError {
+    #[error("IO Error reading snapshot")]
+    IoError(#[from] IoError),
+    #[error("Snapshot ended early")]
+    UnexpectedEof,
+    #[error("Not a snapshot file")]
+    BadMagic,
+    #[error("Invalid value: {0}")]
+    Invalid(String),
+    #[error("Line {line}: {message}")]
+    Text { line: usize, message: String },
+}
+
+/// Compact little-endian binary encoding
+pub trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+pub trait Decode: Sized {
+    /// Decodes a value from the front of `input`, advancing it past what was read
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError>;
+}
+
+/// Line-based text encoding, meant to be readable in a diff. Grid values are written with
+/// `Display` and read back with `FromStr`, so must not contain whitespace.
+pub trait Text: Sized {
+    fn to_text(&self) -> String;
+    fn from_text(s: &str) -> Result<Self, SerialError>;
+}
+
+pub fn to_bytes<E: Encode>(value: &E) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode(&mut out);
+    out
+}
+
+pub fn from_bytes<D: Decode>(mut input: &[u8]) -> Result<D, SerialError> {
+    let value = D::decode(&mut input)?;
+    if !input.is_empty() {
+        return Err(SerialError::Invalid(format!(
+            "{} trailing bytes",
+            input.len()
+        )));
+    }
+    Ok(value)
+}
+
+pub fn write_file<E: Encode>(path: impl AsRef<Path>, value: &E) -> Result<(), SerialError> {
+    let mut out = MAGIC.to_vec();
+    value.encode(&mut out);
+    std::fs::write(path, out)?;
+    Ok(())
+}
+
+pub fn read_file<D: Decode>(path: impl AsRef<Path>) -> Result<D, SerialError> {
+    let bytes = std::fs::read(path)?;
+    match bytes.strip_prefix(MAGIC) {
+        Some(rest) => from_bytes(rest),
+        None => Err(SerialError::BadMagic),
+    }
+}
+
+fn take<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8], SerialError> {
+    if input.len() < n {
+        return Err(SerialError::UnexpectedEof);
+    }
+    let (head, tail) = input.split_at(n);
+    *input = tail;
+    Ok(head)
+}
+
+fn decode_len(input: &mut &[u8]) -> Result<usize, SerialError> {
+    let len = u64::decode(input)?;
+    usize::try_from(len).map_err(|_| SerialError::Invalid(format!("length {}", len)))
+}
+
+macro_rules! int_serial {
+    ($($t:ty),*) => {
+        $(
+            impl Encode for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+
+            impl Decode for $t {
+                fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+                    let bytes = take(input, std::mem::size_of::<$t>())?;
+                    Ok(<$t>::from_le_bytes(bytes.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+int_serial!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl Encode for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out)
+    }
+}
+
+impl Decode for usize {
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        decode_len(input)
+    }
+}
+
+impl Encode for isize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as i64).encode(out)
+    }
+}
+
+impl Decode for isize {
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        let v = i64::decode(input)?;
+        isize::try_from(v).map_err(|_| SerialError::Invalid(format!("isize {}", v)))
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8)
+    }
+}
+
+impl Decode for bool {
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(SerialError::Invalid(format!("bool {}", b))),
+        }
+    }
+}
+
+impl Encode for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u32).encode(out)
+    }
+}
+
+impl Decode for char {
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        let v = u32::decode(input)?;
+        char::from_u32(v).ok_or_else(|| SerialError::Invalid(format!("char {:#x}", v)))
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+}
+
+impl<A: Decode, B: Decode> Decode for (A, B) {
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        Ok((A::decode(input)?, B::decode(input)?))
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for v in self {
+            v.encode(out);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        let len = decode_len(input)?;
+        // Don't trust the length for the allocation - every element is at least a byte
+        let mut values = Vec::with_capacity(len.min(input.len()));
+        for _ in 0..len {
+            values.push(T::decode(input)?);
+        }
+        Ok(values)
+    }
+}
+
+impl<T: Encode> Encode for Point<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.x().encode(out);
+        self.y().encode(out);
+    }
+}
+
+impl<T: Decode> Decode for Point<T> {
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        Ok(Point::new(T::decode(input)?, T::decode(input)?))
+    }
+}
+
+impl<T> Text for Point<T>
+where
+    T: Display + FromStr,
+{
+    fn to_text(&self) -> String {
+        format!("{} {}", self.x(), self.y())
+    }
+
+    fn from_text(s: &str) -> Result<Self, SerialError> {
+        let mut parts = s.split_whitespace().map(|p| parse_token(p, 1));
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(x), Some(y), None) => Ok(Point::new(x?, y?)),
+            _ => Err(text_error(1, "expected `x y`")),
+        }
+    }
+}
+
+impl<T> Encode for SingleVecGrid<T>
+where
+    T: Encode + Default + Clone,
+{
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.width().encode(out);
+        self.height().encode(out);
+        for row in self.rows() {
+            for v in row {
+                v.encode(out);
+            }
+        }
+    }
+}
+
+impl<T> Decode for SingleVecGrid<T>
+where
+    T: Decode + Default + Clone,
+{
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        let (width, height) = decode_dimensions(input, |cells| cells)?;
+        let mut grid = SingleVecGrid::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                grid.set((x, y), T::decode(input)?);
+            }
+        }
+        Ok(grid)
+    }
+}
+
+impl Encode for BitGrid {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.width().encode(out);
+        self.height().encode(out);
+        let mut bits = BitVec::<u8, Lsb0>::with_capacity(self.width() * self.height());
+        for y in 0..self.height() {
+            bits.extend_from_bitslice(self.row_bits(y).unwrap());
+        }
+        out.extend_from_slice(&bits.into_vec());
+    }
+}
+
+impl Decode for BitGrid {
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        let (width, height) = decode_dimensions(input, |cells| cells.div_ceil(8))?;
+        let cells = width * height;
+        let bytes = take(input, cells.div_ceil(8))?;
+        let bits = bytes.view_bits::<Lsb0>();
+        let mut grid = BitGrid::new(width, height);
+        for i in bits[..cells].iter_ones() {
+            grid.set((i % width, i / width), true);
+        }
+        Ok(grid)
+    }
+}
+
+/// Reads a width and height, checking there is at least `min_bytes(cells)` of input left so a
+/// corrupt header can't trigger a huge allocation
+fn decode_dimensions(
+    input: &mut &[u8],
+    min_bytes: fn(usize) -> usize,
+) -> Result<(usize, usize), SerialError> {
+    let width = decode_len(input)?;
+    let height = decode_len(input)?;
+    match width.checked_mul(height) {
+        Some(cells) if min_bytes(cells) <= input.len() => Ok((width, height)),
+        _ => Err(SerialError::UnexpectedEof),
+    }
+}
+
+fn sorted_cells<T>(grid: &SparseGrid<T>) -> Vec<((isize, isize), &T)> {
+    let mut cells = grid.points();
+    cells.sort_by_key(|((x, y), _)| (*y, *x));
+    cells
+}
+
+impl<T: Encode> Encode for SparseGrid<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let cells = sorted_cells(self);
+        cells.len().encode(out);
+        for (coord, v) in cells {
+            coord.encode(out);
+            v.encode(out);
+        }
+    }
+}
+
+impl<T: Decode> Decode for SparseGrid<T> {
+    fn decode(input: &mut &[u8]) -> Result<Self, SerialError> {
+        let len = decode_len(input)?;
+        let mut grid = SparseGrid::new();
+        for _ in 0..len {
+            let coord = <(isize, isize)>::decode(input)?;
+            grid.set(coord, T::decode(input)?);
+        }
+        Ok(grid)
+    }
+}
+
+fn text_error(line: usize, message: impl Into<String>) -> SerialError {
+    SerialError::Text {
+        line,
+        message: message.into(),
+    }
+}
+
+fn parse_token<T: FromStr>(token: &str, line: usize) -> Result<T, SerialError> {
+    token
+        .parse()
+        .map_err(|_| text_error(line, format!("can't parse `{}`", token)))
+}
+
+/// Checks the `<kind> <a> <b>` header line and returns the two numbers
+fn parse_header(header: Option<&str>, kind: &str) -> Result<(usize, usize), SerialError> {
+    let header = header.ok_or_else(|| text_error(1, "missing header"))?;
+    let parts = header.split_whitespace().collect::<Vec<_>>();
+    match parts[..] {
+        [k, a, b] if k == kind => Ok((parse_token(a, 1)?, parse_token(b, 1)?)),
+        _ => Err(text_error(
+            1,
+            format!("expected `{} <width> <height>`", kind),
+        )),
+    }
+}
+
+impl<T> Text for SingleVecGrid<T>
+where
+    T: Display + FromStr + Default + Clone,
+{
+    fn to_text(&self) -> String {
+        let mut out = format!("SingleVecGrid {} {}\n", self.width(), self.height());
+        for row in self.rows() {
+            let row = row.map(|v| v.to_string()).collect::<Vec<_>>();
+            out.push_str(&row.join(" "));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn from_text(s: &str) -> Result<Self, SerialError> {
+        let mut lines = s.lines();
+        let (width, height) = parse_header(lines.next(), "SingleVecGrid")?;
+        let mut grid = SingleVecGrid::new(width, height);
+        for y in 0..height {
+            let line_no = y + 2;
+            let line = lines
+                .next()
+                .ok_or_else(|| text_error(line_no, "missing row"))?;
+            let tokens = line.split_whitespace().collect::<Vec<_>>();
+            if tokens.len() != width {
+                return Err(text_error(
+                    line_no,
+                    format!("expected {} values, got {}", width, tokens.len()),
+                ));
+            }
+            for (x, token) in tokens.into_iter().enumerate() {
+                grid.set((x, y), parse_token(token, line_no)?);
+            }
+        }
+        Ok(grid)
+    }
+}
+
+impl Text for BitGrid {
+    fn to_text(&self) -> String {
+        let mut out = format!("BitGrid {} {}\n", self.width(), self.height());
+        for row in self.rows() {
+            out.extend(row.map(|v| if *v { '#' } else { '.' }));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn from_text(s: &str) -> Result<Self, SerialError> {
+        let mut lines = s.lines();
+        let (width, height) = parse_header(lines.next(), "BitGrid")?;
+        let mut grid = BitGrid::new(width, height);
+        for y in 0..height {
+            let line_no = y + 2;
+            let line = lines
+                .next()
+                .ok_or_else(|| text_error(line_no, "missing row"))?;
+            if line.chars().count() != width {
+                return Err(text_error(line_no, format!("expected {} cells", width)));
+            }
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => grid.set((x, y), true),
+                    '.' => {}
+                    _ => return Err(text_error(line_no, format!("unexpected `{}`", c))),
+                }
+            }
+        }
+        Ok(grid)
+    }
+}
+
+impl<T> Text for SparseGrid<T>
+where
+    T: Display + FromStr,
+{
+    fn to_text(&self) -> String {
+        let mut out = format!("SparseGrid {}\n", self.len());
+        for ((x, y), v) in sorted_cells(self) {
+            out.push_str(&format!("{} {} {}\n", x, y, v));
+        }
+        out
+    }
+
+    fn from_text(s: &str) -> Result<Self, SerialError> {
+        let mut lines = s.lines();
+        let header = lines
+            .next()
+            .ok_or_else(|| text_error(1, "missing header"))?;
+        let len = match header.split_whitespace().collect::<Vec<_>>()[..] {
+            ["SparseGrid", len] => parse_token::<usize>(len, 1)?,
+            _ => return Err(text_error(1, "expected `SparseGrid <count>`")),
+        };
+        let mut grid = SparseGrid::new();
+        for i in 0..len {
+            let line_no = i + 2;
+            let line = lines
+                .next()
+                .ok_or_else(|| text_error(line_no, "missing cell"))?;
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [x, y, v] => grid.set(
+                    (parse_token(x, line_no)?, parse_token(y, line_no)?),
+                    parse_token(v, line_no)?,
+                ),
+                _ => return Err(text_error(line_no, "expected `x y value`")),
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_grid() -> SingleVecGrid<u32> {
+        let mut grid = SingleVecGrid::new(3, 2);
+        for (i, x) in [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+            .iter()
+            .enumerate()
+        {
+            grid.set(*x, i as u32 * 1000);
+        }
+        grid
+    }
+
+    fn sample_bitgrid() -> BitGrid {
+        let mut grid = BitGrid::new(11, 3);
+        grid.set((0, 0), true);
+        grid.set((10, 1), true);
+        grid.set((5, 2), true);
+        grid
+    }
+
+    fn sample_sparse() -> SparseGrid<char> {
+        let mut grid = SparseGrid::new();
+        grid.set((-4, 2), 'a');
+        grid.set((9, -1), 'b');
+        grid.set((0, 0), 'c');
+        grid
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let grid = sample_grid();
+        assert_eq!(
+            from_bytes::<SingleVecGrid<u32>>(&to_bytes(&grid)).unwrap(),
+            grid
+        );
+
+        let bits = sample_bitgrid();
+        let encoded = to_bytes(&bits);
+        assert_eq!(encoded.len(), 8 + 8 + 5);
+        assert_eq!(from_bytes::<BitGrid>(&encoded).unwrap(), bits);
+
+        let sparse = sample_sparse();
+        assert_eq!(
+            from_bytes::<SparseGrid<char>>(&to_bytes(&sparse)).unwrap(),
+            sparse
+        );
+
+        let point = Point::new(-3isize, 7);
+        assert_eq!(
+            from_bytes::<Point<isize>>(&to_bytes(&point)).unwrap(),
+            point
+        );
+
+        let distances = vec![(1usize, 2u64), (3, u64::MAX)];
+        assert_eq!(
+            from_bytes::<Vec<(usize, u64)>>(&to_bytes(&distances)).unwrap(),
+            distances
+        );
+    }
+
+    #[test]
+    fn binary_rejects_bad_input() {
+        let encoded = to_bytes(&sample_grid());
+        assert!(matches!(
+            from_bytes::<SingleVecGrid<u32>>(&encoded[..encoded.len() - 1]),
+            Err(SerialError::UnexpectedEof)
+        ));
+        assert!(matches!(
+            from_bytes::<BitGrid>(&to_bytes(&(usize::MAX, usize::MAX))),
+            Err(SerialError::UnexpectedEof)
+        ));
+        assert!(matches!(
+            from_bytes::<bool>(&[2]),
+            Err(SerialError::Invalid(_))
+        ));
+        assert!(matches!(
+            from_bytes::<u8>(&[1, 2]),
+            Err(SerialError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let grid = sample_grid();
+        let text = grid.to_text();
+        assert_eq!(text, "SingleVecGrid 3 2\n0 1000 2000\n3000 4000 5000\n");
+        assert_eq!(SingleVecGrid::<u32>::from_text(&text).unwrap(), grid);
+
+        let bits = sample_bitgrid();
+        let text = bits.to_text();
+        assert_eq!(
+            text,
+            "BitGrid 11 3\n#..........\n..........#\n.....#.....\n"
+        );
+        assert_eq!(BitGrid::from_text(&text).unwrap(), bits);
+
+        let sparse = sample_sparse();
+        let text = sparse.to_text();
+        assert_eq!(text, "SparseGrid 3\n9 -1 b\n0 0 c\n-4 2 a\n");
+        assert_eq!(SparseGrid::<char>::from_text(&text).unwrap(), sparse);
+
+        let point = Point::new(4, -2);
+        assert_eq!(Point::<i32>::from_text(&point.to_text()).unwrap(), point);
+    }
+
+    #[test]
+    fn text_reports_line() {
+        let err = SingleVecGrid::<u32>::from_text("SingleVecGrid 2 2\n1 2\n3 x\n").unwrap_err();
+        assert!(matches!(err, SerialError::Text { line: 3, .. }));
+        assert!(BitGrid::from_text("BitGrid 2 1\n#\n").is_err());
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let path = std::env::temp_dir().join(format!("aoc-serial-{}", std::process::id()));
+        write_file(&path, &sample_bitgrid()).unwrap();
+        let read: BitGrid = read_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read, sample_bitgrid());
+    }
+}
@@ -0,0 +1,42 @@
+use std::error::Error;
+
+use aoc_2023::commons::cli::Runner;
+
+const DAY: u32 = %day%;
+
+fn part1(input: &str) -> usize {
+    input.lines().count()
+}
+
+fn part2(input: &str) -> usize {
+    input.lines().count()
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut runner = Runner::from_env(DAY);
+    let input = runner.input()?;
+    let input = input.as_str()?;
+
+    runner.part(1, || part1(input));
+    runner.part(2, || part2(input));
+    runner.finish();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(EXAMPLE), 0);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(EXAMPLE), 0);
+    }
+}